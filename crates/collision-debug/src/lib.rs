@@ -1,13 +1,4 @@
-use async_std::task::block_on;
-use wasm_bindgen::prelude::*;
+pub mod sketch;
 
-mod app;
-use app::run_app;
-mod sketch;
-
-#[wasm_bindgen]
-pub async fn main_web() {
-    block_on(async {
-        run_app().await;
-    });
-}
+#[cfg(feature = "standalone")]
+common::main_web!(sketch::create_model, sketch::update);
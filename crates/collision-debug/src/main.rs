@@ -1,12 +1,4 @@
-use async_std::task::block_on;
-
-mod app;
-use app::run_app;
-mod sketch;
-
-fn main() {
-    block_on(async {
-        run_app().await;
-    });
-}
-
+common::main_native!(
+    collision_debug::sketch::create_model,
+    collision_debug::sketch::update
+);
@@ -1,5 +1,9 @@
+use common::camera::Camera;
+use common::draw::draw_body;
+use common::geometry::hit_test;
+use common::link::Link;
+use common::window::{new_window, EguiModel};
 use nannou::prelude::*;
-use nannou::wgpu::{DeviceDescriptor, Limits};
 use nannou_egui::{
     self,
     egui::{self},
@@ -13,8 +17,9 @@ use sylt_2d::{
     math_utils::Vec2,
 };
 
+const DEMO_COUNT: u32 = 8;
+
 pub struct EguiSettings {
-    scale: f32,
     color: Srgb<u8>,
 }
 
@@ -23,38 +28,50 @@ pub struct Model {
     demo_index: u32,
     egui: Egui,
     settings: EguiSettings,
+    camera: Camera,
+    /// The body moved with the mouse, and the last mouse position.
+    drag: Option<(usize, Vec2)>,
+    /// The last mouse position while dragging the view.
+    pan_from: Option<Point2>,
     load_demo_flag: bool,
     contacts: Vec<Contact>,
     bodies: Vec<Body>,
     is_first_frame: bool,
 }
 
+impl EguiModel for Model {
+    fn egui(&mut self) -> &mut Egui {
+        &mut self.egui
+    }
+}
+
 pub async fn create_model(app: &App) -> Model {
-    let _window = app
-        .new_window()
-        .device_descriptor(DeviceDescriptor {
-            limits: Limits {
-                max_texture_dimension_2d: 8192,
-                ..Limits::downlevel_webgl2_defaults()
-            },
-            ..Default::default()
-        })
-        .view(view)
-        .raw_event(raw_window_event)
+    let window = new_window::<Model>(app)
+        .view(|app, model: &Model, frame| view(app, model, &frame))
         .key_pressed(key_pressed)
+        .mouse_pressed(mouse_pressed)
+        .mouse_moved(mouse_moved)
+        .mouse_released(mouse_released)
+        .mouse_wheel(mouse_wheel)
         .build_async()
         .await
         .unwrap();
+    new_model(app, window)
+}
+
+/// Builds the model drawing in an existing window, whose events are passed on
+/// to the handlers of this module.
+pub fn new_model(app: &App, _window: window::Id) -> Model {
     let window = app.window(_window).unwrap();
     let egui = Egui::from_window(&window);
     Model {
         _window,
         demo_index: 6,
         egui,
-        settings: EguiSettings {
-            scale: 30.0,
-            color: WHITE,
-        },
+        settings: EguiSettings { color: WHITE },
+        camera: Camera::new(30.0),
+        drag: None,
+        pan_from: None,
         load_demo_flag: false,
         contacts: Vec::<Contact>::with_capacity(2),
         bodies: Vec::<Body>::with_capacity(2),
@@ -62,6 +79,32 @@ pub async fn create_model(app: &App) -> Model {
     }
 }
 
+/// Applies the demo number and view of a deep link, before the first update.
+pub fn apply_link(model: &mut Model, link: &Link) {
+    if let Some(demo) = &link.demo {
+        match demo.parse::<u32>() {
+            Ok(number @ 1..=DEMO_COUNT) => model.demo_index = number - 1,
+            _ => eprintln!("Error: no demo numbered {}", demo),
+        }
+    }
+    if let Some(scale) = link.scale {
+        model.camera.scale = scale;
+    }
+    if let Some(translate) = link.translate {
+        model.camera.center = Vec2::new(translate[0], translate[1]);
+    }
+}
+
+/// The deep link to the current demo and view.
+pub fn link(model: &Model) -> Link {
+    Link {
+        demo: Some((model.demo_index + 1).to_string()),
+        scale: Some(model.camera.scale),
+        translate: Some([model.camera.center.x, model.camera.center.y]),
+        ..Link::default()
+    }
+}
+
 fn demo1(_model: &mut Model) {
     // Define boxes
     let pos_a = Vec2::new(10.0, 1.0);
@@ -196,41 +239,55 @@ fn demo8(_model: &mut Model) {
     _model.bodies.push(hexagon_body.clone());
     let _ = collide_polygons(&mut _model.contacts, &hexagon_body, &box_a);
 }
-pub fn update(_app: &App, _model: &mut Model, _update: Update) {
-    if _model.is_first_frame {
+pub fn update(app: &App, model: &mut Model, update: Update) {
+    update_hosted(app, model, update, |_| {});
+}
+
+/// Updates the model like `update`, with `host_ui` adding the interface of the
+/// app hosting the example to the egui frame of the example.
+pub fn update_hosted(
+    app: &App,
+    model: &mut Model,
+    update: Update,
+    host_ui: impl FnOnce(&egui::Context),
+) {
+    if let Some(window) = app.window(model._window) {
+        model.camera.set_window(&window);
+    }
+    if model.is_first_frame {
         // Load the initial demo
-        load_demo(_model);
-        _model.is_first_frame = false;
+        load_demo(model);
+        model.is_first_frame = false;
     }
-    if _model.load_demo_flag {
-        load_demo(_model);
-        _model.load_demo_flag = false;
+    if model.load_demo_flag {
+        load_demo(model);
+        model.load_demo_flag = false;
     }
 
-    let egui = &mut _model.egui;
-    let settings = &mut _model.settings;
+    let egui = &mut model.egui;
+    let settings = &mut model.settings;
 
-    egui.set_elapsed_time(_update.since_start);
+    egui.set_elapsed_time(update.since_start);
     let ctx = egui.begin_frame();
 
     egui::Window::new("Settings").show(&ctx, |ui| {
         // Dropdown for selecting the demo
         ui.label("Select Demo:");
         egui::ComboBox::from_label("Demo Selection")
-            .selected_text(format!("Demo {}", _model.demo_index + 1))
+            .selected_text(format!("Demo {}", model.demo_index + 1))
             .show_ui(ui, |ui| {
-                for i in 0..8 {
-                    ui.selectable_value(&mut _model.demo_index, i, format!("Demo {}", i + 1));
+                for i in 0..DEMO_COUNT {
+                    ui.selectable_value(&mut model.demo_index, i, format!("Demo {}", i + 1));
                 }
             });
 
         // Button to load the selected demo
         if ui.button("Load Demo").clicked() {
-            _model.load_demo_flag = true;
+            model.load_demo_flag = true;
         }
-        // Scale slider
+        // Scale slider, also changed with the mouse wheel
         ui.label("Scale:");
-        ui.add(egui::Slider::new(&mut settings.scale, 0.0..=1000.0));
+        ui.add(egui::Slider::new(&mut model.camera.scale, 0.5..=500.0).logarithmic(true));
 
         // Random color button
         let clicked = ui.button("Random color").clicked();
@@ -239,7 +296,10 @@ pub fn update(_app: &App, _model: &mut Model, _update: Update) {
             settings.color = rgb(random(), random(), random());
         }
         ui.label("Use arrows to move the box and perss Return to recalculate the contact points.");
+        ui.label("Drag a body with the mouse to move it, or the middle button to move the view.");
     });
+
+    host_ui(&ctx);
 }
 
 fn load_demo(model: &mut Model) {
@@ -257,12 +317,78 @@ fn load_demo(model: &mut Model) {
         _ => {}
     }
 }
-fn raw_window_event(_app: &App, model: &mut Model, event: &nannou::winit::event::WindowEvent) {
-    // Let egui handle things like keyboard and mouse input.
-    model.egui.handle_raw_event(event);
+fn update_contacts(model: &mut Model) {
+    model.contacts.clear();
+    let (Some(body1), Some(body2)) = (model.bodies.first(), model.bodies.get(1)) else {
+        return;
+    };
+    match (body1.shape, body2.shape) {
+        (Shape::Box, Shape::Box) => {
+            let _ = collide(&mut model.contacts, body1, body2);
+        }
+        _ => {
+            let _ = collide_polygons(&mut model.contacts, body1, body2);
+        }
+    }
+}
+
+pub fn mouse_pressed(app: &App, model: &mut Model, button: MouseButton) {
+    if model.egui.ctx().is_pointer_over_area() {
+        return;
+    }
+    let position = app.mouse.position();
+    match button {
+        MouseButton::Left => {
+            let point = model.camera.screen_to_world(position);
+            model.drag = model
+                .bodies
+                .iter()
+                .rposition(|body| hit_test(body, point))
+                .map(|index| (index, point));
+        }
+        MouseButton::Middle => model.pan_from = Some(position),
+        _ => {}
+    }
+}
+
+pub fn mouse_moved(_app: &App, model: &mut Model, position: Point2) {
+    if let Some(from) = model.pan_from {
+        model
+            .camera
+            .pan(Vec2::new(position.x - from.x, position.y - from.y));
+        model.pan_from = Some(position);
+    }
+    if let Some((index, from)) = model.drag {
+        let point = model.camera.screen_to_world(position);
+        if let Some(body) = model.bodies.get_mut(index) {
+            body.position.x += point.x - from.x;
+            body.position.y += point.y - from.y;
+        }
+        model.drag = Some((index, point));
+    }
+}
+
+pub fn mouse_released(_app: &App, model: &mut Model, _button: MouseButton) {
+    model.pan_from = None;
+    if model.drag.take().is_some() {
+        update_contacts(model);
+    }
+}
+
+pub fn mouse_wheel(app: &App, model: &mut Model, delta: MouseScrollDelta, _phase: TouchPhase) {
+    if model.egui.ctx().is_pointer_over_area() {
+        return;
+    }
+    let lines = match delta {
+        MouseScrollDelta::LineDelta(_, y) => y,
+        MouseScrollDelta::PixelDelta(position) => position.y as f32 / 50.0,
+    };
+    model
+        .camera
+        .zoom_at(app.mouse.position(), 1.1_f32.powf(lines));
 }
 
-fn key_pressed(_app: &App, model: &mut Model, key: Key) {
+pub fn key_pressed(_app: &App, model: &mut Model, key: Key) {
     match key {
         Key::Right => {
             model.bodies.get_mut(1).unwrap().position.x += 0.5;
@@ -277,50 +403,21 @@ fn key_pressed(_app: &App, model: &mut Model, key: Key) {
             model.bodies.get_mut(1).unwrap().position.y -= 0.5;
         }
         Key::Return => {
-            model.contacts.clear();
-            let body1 = model.bodies.first().unwrap();
-            let body2 = model.bodies.get(1).unwrap();
-            match (body1.shape, body2.shape) {
-                (Shape::Box, Shape::Box) => {
-                    let _ = collide(&mut model.contacts, body1, body2);
-                }
-                _ => {
-                    let _ = collide_polygons(&mut model.contacts, body1, body2);
-                }
-            }
+            update_contacts(model);
             println!("Contacts {:?}", model.contacts);
         }
         _other_key => {}
     }
 }
 
-fn view(app: &App, _model: &Model, frame: Frame) {
+pub fn view(app: &App, _model: &Model, frame: &Frame) {
     let draw = app.draw();
-    let draw = draw.scale(_model.settings.scale);
+    let draw = _model.camera.transform(&draw);
     let settings = &_model.settings;
     draw.background().color(SLATEGREY);
     for (num, body) in _model.bodies.iter().enumerate() {
-        match body.shape {
-            Shape::Box => {
-                draw.rect()
-                    .x_y(body.position.x, body.position.y)
-                    .w_h(body.width.x, body.width.y)
-                    .rotate(body.rotation)
-                    .color(if num == 0 { DARKSEAGREEN } else { ORCHID });
-            }
-            Shape::ConvexPolygon => {
-                let tuples: Vec<(f32, f32)> = body
-                    .get_polygon()
-                    .get_vertices()
-                    .into_iter()
-                    .map(Into::into)
-                    .collect();
-                draw.polygon()
-                    .color(if num == 0 { DARKSEAGREEN } else { ORCHID })
-                    .x_y(body.position.x, body.position.y)
-                    .points(tuples);
-            }
-        }
+        let color = if num == 0 { DARKSEAGREEN } else { ORCHID };
+        draw_body(&draw, body, body.position, body.rotation, color);
     }
 
     for contact in _model.contacts.iter() {
@@ -358,6 +455,6 @@ fn view(app: &App, _model: &Model, frame: Frame) {
                     .collect::<Vec<(f32, f32)>>(),
             );
     }*/
-    draw.to_frame(app, &frame).unwrap();
-    _model.egui.draw_to_frame(&frame).unwrap();
+    draw.to_frame(app, frame).unwrap();
+    _model.egui.draw_to_frame(frame).unwrap();
 }
@@ -0,0 +1,45 @@
+/// Runs a nannou app built from an async model function and an update
+/// function, evaluating to a future that completes when the app exits. An
+/// optional event function receives the events of every window.
+#[macro_export]
+macro_rules! run_app {
+    ($create_model:path, $update:path) => {
+        $crate::nannou::app::Builder::new_async(|app| Box::new($create_model(app)))
+            .backends($crate::nannou::wgpu::Backends::PRIMARY | $crate::nannou::wgpu::Backends::GL)
+            .update($update)
+            .run_async()
+    };
+    ($create_model:path, $update:path, $event:path) => {
+        $crate::nannou::app::Builder::new_async(|app| Box::new($create_model(app)))
+            .backends($crate::nannou::wgpu::Backends::PRIMARY | $crate::nannou::wgpu::Backends::GL)
+            .update($update)
+            .event($event)
+            .run_async()
+    };
+}
+
+/// Defines the `main_web` function called by the web page to start the app.
+/// The crate needs `wasm-bindgen` and `wasm-bindgen-futures` as dependencies.
+#[macro_export]
+macro_rules! main_web {
+    ($create_model:path, $update:path $(, $event:path)?) => {
+        #[::wasm_bindgen::prelude::wasm_bindgen]
+        pub async fn main_web() {
+            $crate::block_on(async {
+                $crate::run_app!($create_model, $update $(, $event)?).await;
+            });
+        }
+    };
+}
+
+/// Defines the `main` function running the app natively.
+#[macro_export]
+macro_rules! main_native {
+    ($create_model:path, $update:path $(, $event:path)?) => {
+        fn main() {
+            $crate::block_on(async {
+                $crate::run_app!($create_model, $update $(, $event)?).await;
+            });
+        }
+    };
+}
@@ -0,0 +1,93 @@
+use nannou::prelude::*;
+use sylt_2d::math_utils::Vec2;
+
+const MIN_SCALE: f32 = 0.5;
+const MAX_SCALE: f32 = 500.0;
+/// The share of the window left around the bodies by `fit`.
+const FIT_MARGIN: f32 = 0.1;
+
+/// Maps the world to the window: the world point `center` is drawn at the
+/// centre of the window, with `scale` points per world unit.
+///
+/// Window coordinates are nannou's: logical points with the origin at the
+/// centre of the window and y pointing up. The mouse positions of nannou and
+/// the touch positions of egui are already in logical points, so the device
+/// pixel ratio of a web canvas needs no handling here. The window size is kept
+/// up to date with `set_window`.
+#[derive(Debug, Clone, Copy)]
+pub struct Camera {
+    pub scale: f32,
+    pub center: Vec2,
+    window: Rect,
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Camera::new(18.0)
+    }
+}
+
+impl Camera {
+    pub fn new(scale: f32) -> Camera {
+        Camera {
+            scale,
+            center: Vec2::new(0.0, 0.0),
+            window: Rect::from_w_h(0.0, 0.0),
+        }
+    }
+
+    /// Follows the size of `window`, to be called when the window is resized.
+    pub fn set_window(&mut self, window: &Window) {
+        self.window = window.rect();
+    }
+
+    /// Maps a point in window coordinates to world coordinates.
+    pub fn screen_to_world(&self, point: Point2) -> Vec2 {
+        Vec2::new(
+            point.x / self.scale + self.center.x,
+            point.y / self.scale + self.center.y,
+        )
+    }
+
+    /// Maps a point in world coordinates to window coordinates.
+    pub fn world_to_screen(&self, point: Vec2) -> Point2 {
+        pt2(
+            (point.x - self.center.x) * self.scale,
+            (point.y - self.center.y) * self.scale,
+        )
+    }
+
+    /// Maps a point in logical points from the top left corner of the window,
+    /// as used by egui, to window coordinates.
+    pub fn from_top_left(&self, x: f32, y: f32) -> Point2 {
+        pt2(x - 0.5 * self.window.w(), 0.5 * self.window.h() - y)
+    }
+
+    /// Applies the camera to `draw`, so that it draws in world coordinates.
+    pub fn transform(&self, draw: &Draw) -> Draw {
+        draw.scale(self.scale).x_y(-self.center.x, -self.center.y)
+    }
+
+    /// Zooms by `factor`, keeping the world point under `screen_point` in place.
+    pub fn zoom_at(&mut self, screen_point: Point2, factor: f32) {
+        let before = self.screen_to_world(screen_point);
+        self.scale = (self.scale * factor).clamp(MIN_SCALE, MAX_SCALE);
+        let after = self.screen_to_world(screen_point);
+        self.center = self.center + before - after;
+    }
+
+    /// Moves the view along with a drag of `delta` in window coordinates.
+    pub fn pan(&mut self, delta: Vec2) {
+        self.center = self.center - delta * (1.0 / self.scale);
+    }
+
+    /// Centres the view on the box from `min` to `max` and zooms to show it all
+    /// in the window.
+    pub fn fit(&mut self, min: Vec2, max: Vec2) {
+        self.center = Vec2::new(0.5 * (min.x + max.x), 0.5 * (min.y + max.y));
+        let width = (max.x - min.x).max(f32::EPSILON);
+        let height = (max.y - min.y).max(f32::EPSILON);
+        let scale = (self.window.w() / width).min(self.window.h() / height) * (1.0 - FIT_MARGIN);
+        self.scale = scale.clamp(MIN_SCALE, MAX_SCALE);
+    }
+}
@@ -0,0 +1,37 @@
+use nannou::color::IntoLinSrgba;
+use nannou::prelude::*;
+use sylt_2d::body::{Body, Shape};
+use sylt_2d::math_utils::Vec2;
+
+/// Draws `body` at `position`, rotated by `rotation`, which may differ from
+/// the state of the body when drawing an interpolated pose.
+pub fn draw_body(
+    draw: &Draw,
+    body: &Body,
+    position: Vec2,
+    rotation: f32,
+    color: impl IntoLinSrgba<f32>,
+) {
+    match body.shape {
+        Shape::Box => {
+            draw.rect()
+                .x_y(position.x, position.y)
+                .w_h(body.width.x, body.width.y)
+                .rotate(rotation)
+                .color(color);
+        }
+        Shape::ConvexPolygon => {
+            let tuples: Vec<(f32, f32)> = body
+                .get_polygon()
+                .get_vertices()
+                .into_iter()
+                .map(Into::into)
+                .collect();
+            draw.polygon()
+                .color(color)
+                .x_y(position.x, position.y)
+                .rotate(rotation)
+                .points(tuples);
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use sylt_2d::body::{Body, Shape};
+use sylt_2d::math_utils::Vec2;
+
+/// Rotates `v` by `angle` radians.
+pub fn rotate(v: Vec2, angle: f32) -> Vec2 {
+    let (sin, cos) = angle.sin_cos();
+    Vec2::new(cos * v.x - sin * v.y, sin * v.x + cos * v.y)
+}
+
+pub fn cross(a: Vec2, b: Vec2) -> f32 {
+    a.x * b.y - a.y * b.x
+}
+
+/// Converts a point from world coordinates into the local frame of `body`.
+pub fn to_local(body: &Body, point: Vec2) -> Vec2 {
+    rotate(point - body.position, -body.rotation)
+}
+
+/// Converts a point from the local frame of `body` into world coordinates.
+pub fn to_world(body: &Body, point: Vec2) -> Vec2 {
+    body.position + rotate(point, body.rotation)
+}
+
+/// Whether the world space `point` lies inside `body`.
+pub fn hit_test(body: &Body, point: Vec2) -> bool {
+    let local = to_local(body, point);
+    match body.shape {
+        Shape::Box => local.x.abs() <= 0.5 * body.width.x && local.y.abs() <= 0.5 * body.width.y,
+        Shape::ConvexPolygon => {
+            let vertices = body.get_polygon().get_vertices();
+            let mut sign = 0.0;
+            for (i, &a) in vertices.iter().enumerate() {
+                let b = vertices[(i + 1) % vertices.len()];
+                let side = cross(b - a, local - a);
+                if side * sign < 0.0 {
+                    return false;
+                }
+                if side != 0.0 {
+                    sign = side;
+                }
+            }
+            !vertices.is_empty()
+        }
+    }
+}
@@ -0,0 +1,33 @@
+//! Code shared by the example crates: the app and window bootstrap, the
+//! camera, the drawing and hit testing of bodies and the deep links to a
+//! configuration.
+//!
+//! An example only provides a model, an `update` function and a `view`:
+//!
+//! ```ignore
+//! // lib.rs, built to wasm
+//! mod sketch;
+//! common::main_web!(sketch::create_model, sketch::update);
+//!
+//! // main.rs, run natively
+//! mod sketch;
+//! common::main_native!(sketch::create_model, sketch::update);
+//! ```
+
+#[cfg(feature = "gui")]
+pub mod app;
+#[cfg(feature = "gui")]
+pub mod camera;
+#[cfg(feature = "gui")]
+pub mod draw;
+pub mod geometry;
+pub mod link;
+#[cfg(feature = "gui")]
+pub mod window;
+
+#[cfg(feature = "gui")]
+pub use async_std::task::block_on;
+#[cfg(feature = "gui")]
+pub use nannou;
+#[cfg(feature = "gui")]
+pub use nannou_egui;
@@ -0,0 +1,247 @@
+use std::str::FromStr;
+
+/// The settings of a deep link, read from and written to the query string of
+/// the page URL, as in `?mode=samples&demo=pyramid&scale=30&paused=true`.
+///
+/// Each parameter is optional: the ones missing from the link keep the
+/// defaults of the app. What a parameter means, such as the name of a demo, is
+/// up to the example reading it.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Link {
+    pub mode: Option<String>,
+    pub demo: Option<String>,
+    /// Points per world unit of the camera.
+    pub scale: Option<f32>,
+    /// The world point at the centre of the view.
+    pub translate: Option<[f32; 2]>,
+    pub gravity: Option<[f32; 2]>,
+    pub seed: Option<u64>,
+    pub paused: Option<bool>,
+}
+
+impl Link {
+    /// Reads the parameters of `query`, with or without its leading `?`.
+    /// Invalid values are reported and left out, unknown keys are ignored.
+    pub fn parse(query: &str) -> Link {
+        let mut link = Link::default();
+        let pairs = query.trim_start_matches('?').split('&');
+        for pair in pairs.filter(|pair| !pair.is_empty()) {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let value = decode(value);
+            let valid = match key {
+                "mode" => set(&mut link.mode, &value),
+                "demo" => set(&mut link.demo, &value),
+                "scale" => {
+                    set(&mut link.scale, &value);
+                    link.scale = link.scale.filter(|scale| scale.is_finite() && *scale > 0.0);
+                    link.scale.is_some()
+                }
+                "translate" => set_pair(&mut link.translate, &value),
+                "gravity" => set_pair(&mut link.gravity, &value),
+                "seed" => set(&mut link.seed, &value),
+                "paused" => set(&mut link.paused, &value),
+                _ => true,
+            };
+            if !valid {
+                eprintln!("Error: invalid link parameter {}={}", key, value);
+            }
+        }
+        link
+    }
+
+    /// The query string of the link, without the leading `?`.
+    pub fn to_query(&self) -> String {
+        let pair = |[x, y]: [f32; 2]| format!("{},{}", x, y);
+        let params = [
+            ("mode", self.mode.clone()),
+            ("demo", self.demo.clone()),
+            ("scale", self.scale.map(|scale| scale.to_string())),
+            ("translate", self.translate.map(pair)),
+            ("gravity", self.gravity.map(pair)),
+            ("seed", self.seed.map(|seed| seed.to_string())),
+            ("paused", self.paused.map(|paused| paused.to_string())),
+        ];
+        params
+            .iter()
+            .filter_map(|(key, value)| Some(format!("{}={}", key, encode(value.as_ref()?))))
+            .collect::<Vec<_>>()
+            .join("&")
+    }
+}
+
+fn set<T: FromStr>(field: &mut Option<T>, value: &str) -> bool {
+    *field = value.parse().ok();
+    field.is_some()
+}
+
+fn set_pair(field: &mut Option<[f32; 2]>, value: &str) -> bool {
+    *field = value
+        .split_once(',')
+        .and_then(|(x, y)| Some([x.trim().parse().ok()?, y.trim().parse().ok()?]))
+        .filter(|pair: &[f32; 2]| pair.iter().all(|v| v.is_finite()));
+    field.is_some()
+}
+
+/// Percent-encodes `value` for a query string, keeping the characters that
+/// need no escaping.
+fn encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b',' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+/// Decodes a percent-encoded query value, where `+` stands for a space.
+fn decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = bytes
+            .get(i + 1..i + 3)
+            .filter(|hex| bytes[i] == b'%' && hex.iter().all(u8::is_ascii_hexdigit))
+            .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+        match (bytes[i], escaped) {
+            (_, Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (b'+', None) => {
+                decoded.push(b' ');
+                i += 1;
+            }
+            (byte, None) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// The query string of the page URL, empty when running natively.
+#[cfg(target_arch = "wasm32")]
+pub fn page_query() -> String {
+    web_sys::window()
+        .and_then(|window| window.location().search().ok())
+        .unwrap_or_default()
+}
+
+/// The query string of the page URL, empty when running natively.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn page_query() -> String {
+    String::new()
+}
+
+/// The URL of the page with `query` as its query string. Natively, where there
+/// is no page, only the query string.
+#[cfg(target_arch = "wasm32")]
+pub fn page_url(query: &str) -> String {
+    let location = web_sys::window().map(|window| window.location());
+    let base = location
+        .and_then(|location| Some(location.origin().ok()? + &location.pathname().ok()?))
+        .unwrap_or_default();
+    format!("{}?{}", base, query)
+}
+
+/// The URL of the page with `query` as its query string. Natively, where there
+/// is no page, only the query string.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn page_url(query: &str) -> String {
+    format!("?{}", query)
+}
+
+/// Copies `text` to the clipboard of the browser.
+#[cfg(target_arch = "wasm32")]
+pub fn copy_to_clipboard(text: &str) -> Result<(), String> {
+    let window = web_sys::window().ok_or("no window")?;
+    // The copy finishes in the background, there is nothing to wait for
+    let _ = window.navigator().clipboard().write_text(text);
+    Ok(())
+}
+
+/// Copies `text` to the clipboard of the browser.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn copy_to_clipboard(_text: &str) -> Result<(), String> {
+    Err("copying to the clipboard is only supported on the web".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_the_query() {
+        let link = Link {
+            mode: Some("samples".to_string()),
+            demo: Some("pyramid".to_string()),
+            scale: Some(30.5),
+            translate: Some([-1.25, 4.0]),
+            gravity: Some([0.0, -9.81]),
+            seed: Some(42),
+            paused: Some(true),
+        };
+        assert_eq!(Link::parse(&link.to_query()), link);
+        assert_eq!(Link::parse(&format!("?{}", link.to_query())), link);
+        assert_eq!(Link::default().to_query(), "");
+    }
+
+    #[test]
+    fn drops_invalid_values_and_unknown_keys() {
+        let queries = [
+            "scale=0",
+            "scale=-2",
+            "scale=inf",
+            "scale=NaN",
+            "translate=1",
+            "translate=1,NaN",
+            "gravity=0,-inf",
+            "seed=-1",
+            "paused=maybe",
+            "unknown=1",
+            "mode",
+        ];
+        for query in queries {
+            let expected = match query {
+                // A key without a value is an empty value
+                "mode" => Link {
+                    mode: Some(String::new()),
+                    ..Link::default()
+                },
+                _ => Link::default(),
+            };
+            assert_eq!(Link::parse(query), expected, "{}", query);
+        }
+        let link = Link::parse("scale=-1&seed=7&scale=2");
+        assert_eq!((link.scale, link.seed), (Some(2.0), Some(7)));
+    }
+
+    #[test]
+    fn percent_encodes_the_demo_name() {
+        let link = Link {
+            demo: Some("Pyramid & stack=1/2 \u{e9}".to_string()),
+            ..Link::default()
+        };
+        let query = link.to_query();
+        assert_eq!(query, "demo=Pyramid%20%26%20stack%3D1%2F2%20%C3%A9");
+        assert_eq!(Link::parse(&query), link);
+        assert_eq!(
+            Link::parse("demo=Simple+Pendulum").demo.as_deref(),
+            Some("Simple Pendulum")
+        );
+    }
+
+    #[test]
+    fn keeps_malformed_escapes() {
+        assert_eq!(decode("%+5"), "% 5");
+        assert_eq!(decode("%-1"), "%-1");
+        assert_eq!(decode("%4"), "%4");
+        assert_eq!(decode("%4a%4A"), "JJ");
+    }
+}
@@ -0,0 +1,32 @@
+use nannou::prelude::*;
+use nannou::wgpu::{DeviceDescriptor, Limits};
+use nannou::winit::event::WindowEvent;
+use nannou_egui::Egui;
+
+/// A model drawing an egui interface in its window.
+pub trait EguiModel {
+    fn egui(&mut self) -> &mut Egui;
+}
+
+/// Starts building a window whose device limits fit WebGL2, with egui
+/// receiving the raw window events of the model.
+pub fn new_window<M: EguiModel + 'static>(app: &App) -> window::Builder<'_> {
+    new_webgl2_window(app).raw_event(raw_window_event::<M>)
+}
+
+/// Starts building a window whose device limits fit WebGL2, leaving the
+/// handling of its events to the caller.
+pub fn new_webgl2_window(app: &App) -> window::Builder<'_> {
+    app.new_window().device_descriptor(DeviceDescriptor {
+        limits: Limits {
+            max_texture_dimension_2d: 8192,
+            ..Limits::downlevel_webgl2_defaults()
+        },
+        ..Default::default()
+    })
+}
+
+fn raw_window_event<M: EguiModel>(_app: &App, model: &mut M, event: &WindowEvent) {
+    // Let egui handle things like keyboard and mouse input.
+    model.egui().handle_raw_event(event);
+}
@@ -0,0 +1,32 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Embeds every `scenes/*.json` file into the binary so that adding a scene
+// only means dropping a new file into the directory.
+fn main() {
+    let scenes_dir = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).join("scenes");
+    println!("cargo:rerun-if-changed={}", scenes_dir.display());
+
+    let mut files: Vec<PathBuf> = fs::read_dir(&scenes_dir)
+        .unwrap()
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    files.sort();
+
+    let mut out = String::from("pub const BUILTIN_SCENES: &[(&str, &str)] = &[\n");
+    for path in files {
+        println!("cargo:rerun-if-changed={}", path.display());
+        let file_name = path.file_name().unwrap().to_string_lossy();
+        out.push_str(&format!(
+            "    ({:?}, include_str!({:?})),\n",
+            file_name,
+            path.display().to_string()
+        ));
+    }
+    out.push_str("];\n");
+
+    let out_path = Path::new(&env::var("OUT_DIR").unwrap()).join("scenes.rs");
+    fs::write(out_path, out).unwrap();
+}
@@ -0,0 +1,117 @@
+use js_sys::{Float32Array, Function};
+use wasm_bindgen::prelude::*;
+
+use crate::headless::find_builtin_scene_index;
+use crate::remote::{self, Request, SharedRemote};
+use crate::scene::Scene;
+
+/// The number of values per body in `get_bodies`.
+const BODY_STRIDE: usize = 6;
+
+/// Drives the samples app running on the page from JavaScript.
+///
+/// Requests are taken on the next frame of the app:
+///
+/// ```js
+/// const simulation = new Simulation();
+/// simulation.load_demo("05-pyramid-stacking");
+/// simulation.on_collision((body1, body2, step) => console.log(body1, body2, step));
+/// ```
+#[wasm_bindgen]
+pub struct Simulation {
+    remote: SharedRemote,
+}
+
+impl Default for Simulation {
+    fn default() -> Self {
+        Simulation::new()
+    }
+}
+
+impl Simulation {
+    /// A handle on the app connected to `remote`.
+    pub fn with_remote(remote: SharedRemote) -> Simulation {
+        Simulation { remote }
+    }
+}
+
+#[wasm_bindgen]
+impl Simulation {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Simulation {
+        Simulation {
+            remote: remote::shared(),
+        }
+    }
+
+    fn request(&self, request: Request) {
+        self.remote.borrow_mut().requests.push(request);
+    }
+
+    /// Loads a built-in demo by its number starting at 1, its file name or its
+    /// name.
+    pub fn load_demo(&self, name: &str) -> Result<(), JsError> {
+        let index = find_builtin_scene_index(name)
+            .ok_or_else(|| JsError::new(&format!("no demo named {}", name)))?;
+        self.request(Request::LoadDemo(index));
+        Ok(())
+    }
+
+    /// Loads a scene from its JSON description, as exported by the app.
+    pub fn load_scene(&self, json: &str) -> Result<(), JsError> {
+        let scene = Scene::from_json(json).map_err(|e| JsError::new(&e.to_string()))?;
+        self.request(Request::LoadScene(scene));
+        Ok(())
+    }
+
+    /// Takes `n` steps, paused or not.
+    pub fn step(&self, n: u32) {
+        self.request(Request::Step(n));
+    }
+
+    pub fn pause(&self) {
+        self.request(Request::SetPaused(true));
+    }
+
+    pub fn resume(&self) {
+        self.request(Request::SetPaused(false));
+    }
+
+    pub fn set_gravity(&self, x: f32, y: f32) {
+        self.request(Request::SetGravity([x, y]));
+    }
+
+    /// The state of the bodies after the last frame, as `x, y, rotation, vx,
+    /// vy, angular_velocity` for each body in turn.
+    pub fn get_bodies(&self) -> Float32Array {
+        let remote = self.remote.borrow();
+        let mut values = Vec::with_capacity(remote.bodies.len() * BODY_STRIDE);
+        for body in &remote.bodies {
+            values.extend_from_slice(&[
+                body.position[0],
+                body.position[1],
+                body.rotation,
+                body.velocity[0],
+                body.velocity[1],
+                body.angular_velocity,
+            ]);
+        }
+        Float32Array::from(values.as_slice())
+    }
+
+    /// Calls `callback` with the indices of two bodies and the step number
+    /// whenever the bodies start touching.
+    pub fn on_collision(&self, callback: Function) {
+        self.remote.borrow_mut().on_collision(move |collision| {
+            let result = callback.call3(
+                &JsValue::NULL,
+                &collision.body_1.into(),
+                &collision.body_2.into(),
+                &(collision.step as f64).into(),
+            );
+            if let Err(e) = result {
+                eprintln!("Error: {:?}", e);
+            }
+        });
+    }
+}
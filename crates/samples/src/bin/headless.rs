@@ -0,0 +1,136 @@
+use std::env;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::process::ExitCode;
+
+use samples::diagnostics::Diagnostics;
+use samples::headless::{find_builtin_scene, run, BodyState, RunConfig};
+
+const USAGE: &str = "\
+Usage: headless <demo> [options]
+
+Steps a built-in demo without a window and prints the body states as CSV.
+<demo> is the demo number starting at 1, its file name or its name.
+
+Options:
+    --steps <n>        Number of steps to take (default 600)
+    --dt <seconds>     Time step (default 1/60)
+    --iterations <n>   Solver iterations, overriding the scene
+    --seed <n>         Seed of the random position jitter (default 0)
+    --every <n>        Print the states every n steps, 0 for the last one only (default 1)
+    --diagnostics      Print the energy, momentum, penetration and joint drift
+                       of the world instead of the body states
+    --output <file>    Write the states to a file instead of stdout";
+
+struct Args {
+    demo: String,
+    config: RunConfig,
+    every: u32,
+    diagnostics: bool,
+    output: Option<String>,
+}
+
+fn parse_value<T: std::str::FromStr>(name: &str, value: Option<String>) -> Result<T, String> {
+    let value = value.ok_or_else(|| format!("Missing value for {}", name))?;
+    value
+        .parse()
+        .map_err(|_| format!("Invalid value for {}: {}", name, value))
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = env::args().skip(1);
+    let mut demo = None;
+    let mut config = RunConfig::default();
+    let mut every = 1;
+    let mut diagnostics = false;
+    let mut output = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--steps" => config.steps = parse_value(&arg, args.next())?,
+            "--dt" => config.time_step = parse_value(&arg, args.next())?,
+            "--iterations" => config.iterations = Some(parse_value(&arg, args.next())?),
+            "--seed" => config.seed = parse_value(&arg, args.next())?,
+            "--every" => every = parse_value(&arg, args.next())?,
+            "--diagnostics" => diagnostics = true,
+            "--output" => output = Some(parse_value(&arg, args.next())?),
+            "-h" | "--help" => return Err(USAGE.to_string()),
+            _ if arg.starts_with('-') => return Err(format!("Unknown option {}", arg)),
+            _ if demo.is_none() => demo = Some(arg),
+            _ => return Err(format!("Unexpected argument {}", arg)),
+        }
+    }
+    if config.time_step <= 0.0 {
+        return Err("The time step must be positive".to_string());
+    }
+    Ok(Args {
+        demo: demo.ok_or_else(|| USAGE.to_string())?,
+        config,
+        every,
+        diagnostics,
+        output,
+    })
+}
+
+fn write_states(out: &mut impl Write, step: u32, states: &[BodyState]) -> io::Result<()> {
+    for (body, state) in states.iter().enumerate() {
+        state.write_csv(out, step, body)?;
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let Some(scene) = find_builtin_scene(&args.demo) else {
+        eprintln!("Error: no demo matches {:?}", args.demo);
+        return ExitCode::FAILURE;
+    };
+
+    let mut out: Box<dyn Write> = match &args.output {
+        Some(path) => match File::create(path) {
+            Ok(file) => Box::new(BufWriter::new(file)),
+            Err(e) => {
+                eprintln!("Error: {}: {}", path, e);
+                return ExitCode::FAILURE;
+            }
+        },
+        None => Box::new(BufWriter::new(io::stdout().lock())),
+    };
+
+    let steps = args.config.steps;
+    let header = if args.diagnostics {
+        Diagnostics::CSV_HEADER
+    } else {
+        BodyState::CSV_HEADER
+    };
+    let mut result = writeln!(out, "{}", header);
+    let outcome = run(&scene, &args.config, |step, world| {
+        let due = if args.every == 0 {
+            step == steps
+        } else {
+            step % args.every == 0 || step == steps
+        };
+        if !due || result.is_err() {
+            return;
+        }
+        result = if args.diagnostics {
+            Diagnostics::of_world(world).write_csv(&mut out, step)
+        } else {
+            write_states(&mut out, step, &BodyState::of_world(world))
+        };
+    });
+    if let Err(e) = outcome {
+        eprintln!("Error: {}", e);
+        return ExitCode::FAILURE;
+    }
+    if let Err(e) = result.and_then(|()| out.flush()) {
+        eprintln!("Error: {}", e);
+        return ExitCode::FAILURE;
+    }
+    ExitCode::SUCCESS
+}
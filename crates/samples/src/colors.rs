@@ -0,0 +1,156 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::{Rc, Weak};
+
+use nannou::color::{IntoLinSrgba, LinSrgba};
+use nannou::prelude::*;
+use sylt_2d::body::Body;
+use sylt_2d::world::World;
+
+use crate::picking::is_static;
+
+/// Below this linear and angular speed a body counts as resting.
+const REST_SPEED: f32 = 0.05;
+
+/// How the bodies of the world are coloured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorMode {
+    StaticDynamic,
+    RestingMoving,
+    RandomHue,
+    SpeedHeatMap,
+    ImpulseHeatMap,
+}
+
+impl ColorMode {
+    pub const ALL: [ColorMode; 5] = [
+        ColorMode::StaticDynamic,
+        ColorMode::RestingMoving,
+        ColorMode::RandomHue,
+        ColorMode::SpeedHeatMap,
+        ColorMode::ImpulseHeatMap,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ColorMode::StaticDynamic => "Static / dynamic",
+            ColorMode::RestingMoving => "Resting / moving",
+            ColorMode::RandomHue => "Random hue",
+            ColorMode::SpeedHeatMap => "Heat map of speed",
+            ColorMode::ImpulseHeatMap => "Heat map of contact impulse",
+        }
+    }
+}
+
+/// Gives each body a key that stays the same when other bodies are removed or
+/// reordered, numbering the bodies in the order they show up.
+#[derive(Default)]
+pub struct BodyKeys {
+    keys: HashMap<*const RefCell<Body>, (Weak<RefCell<Body>>, u32)>,
+    next: u32,
+}
+
+impl BodyKeys {
+    /// Starts over with a newly built `world`, whose bodies keep the keys in
+    /// `keys` they had in an earlier world, if any.
+    pub fn reset(&mut self, world: &World, keys: impl Iterator<Item = Option<u32>>) {
+        self.keys.clear();
+        let keys: Vec<_> = keys.collect();
+        self.next = keys.iter().flatten().map(|key| key + 1).max().unwrap_or(0);
+        for (body, key) in world.bodies.iter().zip(keys) {
+            if let Some(key) = key {
+                self.keys
+                    .insert(Rc::as_ptr(body), (Rc::downgrade(body), key));
+            }
+        }
+        self.update(world);
+    }
+
+    /// Numbers the bodies added to `world` since the last update.
+    pub fn update(&mut self, world: &World) {
+        // A dropped body may leave its address to a new one
+        self.keys.retain(|_, (body, _)| body.strong_count() > 0);
+        for body in world.bodies.iter() {
+            let next = &mut self.next;
+            self.keys.entry(Rc::as_ptr(body)).or_insert_with(|| {
+                *next += 1;
+                (Rc::downgrade(body), *next - 1)
+            });
+        }
+    }
+
+    pub fn key(&self, body: &Rc<RefCell<Body>>) -> Option<u32> {
+        self.keys.get(&Rc::as_ptr(body)).map(|&(_, key)| key)
+    }
+}
+
+fn speed(body: &Body) -> f32 {
+    body.velocity.x.hypot(body.velocity.y)
+}
+
+/// The normal impulse applied by the contacts of each body of `world`.
+fn contact_impulses(world: &World) -> Vec<f32> {
+    let mut impulses = vec![0.0; world.bodies.len()];
+    for (_, arbiter) in world.arbiters.iter() {
+        let impulse: f32 = arbiter.contacts.iter().flatten().map(|c| c.pn).sum();
+        for (i, body) in world.bodies.iter().enumerate() {
+            if Rc::ptr_eq(body, &arbiter.body_1) || Rc::ptr_eq(body, &arbiter.body_2) {
+                impulses[i] += impulse;
+            }
+        }
+    }
+    impulses
+}
+
+/// Blue for zero up to red for `value >= max`.
+fn heat(value: f32, max: f32) -> LinSrgba {
+    let t = if max > 0.0 {
+        (value / max).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    hsl((1.0 - t) * 0.66, 0.9, 0.5).into_lin_srgba()
+}
+
+/// The colour of each body of `world` in `mode`. Static bodies always get the
+/// same colour.
+pub fn body_colors(world: &World, mode: ColorMode, keys: &BodyKeys) -> Vec<LinSrgba> {
+    // Heat maps are relative to the hottest dynamic body of the frame
+    let values: Vec<f32> = match mode {
+        ColorMode::SpeedHeatMap => world.iter_bodies().map(|body| speed(&body)).collect(),
+        ColorMode::ImpulseHeatMap => contact_impulses(world),
+        _ => Vec::new(),
+    };
+    let max = world
+        .iter_bodies()
+        .zip(values.iter())
+        .filter(|(body, _)| !is_static(body))
+        .map(|(_, &value)| value)
+        .fold(0.0, f32::max);
+
+    world
+        .iter_bodies()
+        .enumerate()
+        .map(|(i, body)| {
+            if is_static(&body) {
+                return DARKSEAGREEN.into_lin_srgba();
+            }
+            match mode {
+                ColorMode::StaticDynamic => ORCHID.into_lin_srgba(),
+                ColorMode::RestingMoving => {
+                    if speed(&body) < REST_SPEED && body.angular_velocity.abs() < REST_SPEED {
+                        SLATEBLUE.into_lin_srgba()
+                    } else {
+                        ORCHID.into_lin_srgba()
+                    }
+                }
+                ColorMode::RandomHue => {
+                    let key = keys.key(&world.bodies[i]).unwrap_or(i as u32);
+                    let hue = (key as f32 * 0.618_034).fract();
+                    hsl(hue, 0.7, 0.6).into_lin_srgba()
+                }
+                ColorMode::SpeedHeatMap | ColorMode::ImpulseHeatMap => heat(values[i], max),
+            }
+        })
+        .collect()
+}
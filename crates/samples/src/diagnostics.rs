@@ -0,0 +1,100 @@
+use std::io::{self, Write};
+
+use sylt_2d::math_utils::{Mat2x2, Vec2};
+use sylt_2d::world::World;
+
+use crate::picking::is_static;
+
+fn cross(a: Vec2, b: Vec2) -> f32 {
+    a.x * b.y - a.y * b.x
+}
+
+fn length(v: Vec2) -> f32 {
+    v.x.hypot(v.y)
+}
+
+/// Quantities the solver should keep in check, measured on the state of a
+/// world after a step. Static bodies are left out.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Diagnostics {
+    pub kinetic_energy: f32,
+    /// The gravitational potential energy, zero at the origin.
+    pub potential_energy: f32,
+    pub linear_momentum: [f32; 2],
+    /// The angular momentum around the origin.
+    pub angular_momentum: f32,
+    /// The number of contact points between all the bodies.
+    pub contact_count: usize,
+    /// The deepest overlap among the contact points, zero without overlap.
+    pub max_penetration: f32,
+    /// The largest distance between the two anchors of a joint.
+    pub max_joint_drift: f32,
+}
+
+impl Diagnostics {
+    pub const CSV_HEADER: &'static str = "step,kinetic_energy,potential_energy,total_energy,\
+        linear_momentum_x,linear_momentum_y,angular_momentum,contact_count,max_penetration,max_joint_drift";
+
+    pub fn of_world(world: &World) -> Diagnostics {
+        let mut diagnostics = Diagnostics::default();
+        for body in world.iter_bodies().filter(|body| !is_static(body)) {
+            let speed = length(body.velocity);
+            diagnostics.kinetic_energy += 0.5 * body.mass * speed * speed
+                + 0.5 * body.inertia * body.angular_velocity * body.angular_velocity;
+            diagnostics.potential_energy -=
+                body.mass * (world.gravity.x * body.position.x + world.gravity.y * body.position.y);
+            diagnostics.linear_momentum[0] += body.mass * body.velocity.x;
+            diagnostics.linear_momentum[1] += body.mass * body.velocity.y;
+            diagnostics.angular_momentum += body.inertia * body.angular_velocity
+                + body.mass * cross(body.position, body.velocity);
+        }
+
+        let contacts = || {
+            world
+                .arbiters
+                .values()
+                .flat_map(|arbiter| arbiter.contacts.iter().flatten())
+        };
+        diagnostics.contact_count = contacts().count();
+        diagnostics.max_penetration = contacts()
+            .map(|contact| -contact.separation)
+            .fold(0.0, f32::max);
+
+        diagnostics.max_joint_drift = world
+            .joints
+            .iter()
+            .map(|joint| {
+                let body_1 = joint.body_1.borrow();
+                let body_2 = joint.body_2.borrow();
+                let anchor_1 = body_1.position
+                    + Mat2x2::new_from_angle(body_1.rotation) * joint.local_anchor_1;
+                let anchor_2 = body_2.position
+                    + Mat2x2::new_from_angle(body_2.rotation) * joint.local_anchor_2;
+                length(anchor_2 - anchor_1)
+            })
+            .fold(0.0, f32::max);
+
+        diagnostics
+    }
+
+    pub fn total_energy(&self) -> f32 {
+        self.kinetic_energy + self.potential_energy
+    }
+
+    pub fn write_csv(&self, out: &mut impl Write, step: u32) -> io::Result<()> {
+        writeln!(
+            out,
+            "{},{},{},{},{},{},{},{},{},{}",
+            step,
+            self.kinetic_energy,
+            self.potential_energy,
+            self.total_energy(),
+            self.linear_momentum[0],
+            self.linear_momentum[1],
+            self.angular_momentum,
+            self.contact_count,
+            self.max_penetration,
+            self.max_joint_drift
+        )
+    }
+}
@@ -0,0 +1,479 @@
+use common::geometry::{hit_test, to_local, to_world};
+use nannou_egui::egui;
+use sylt_2d::body::Body;
+use sylt_2d::math_utils::Vec2;
+
+use crate::scene::{to_array, vec2, BodyDesc, JointDesc, Scene, ShapeDesc};
+use crate::spring::SpringConfig;
+
+const MIN_SIZE: f32 = 0.1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tool {
+    Select,
+    AddBox,
+    AddPolygon,
+    AddJoint,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Handle {
+    Move,
+    Resize,
+    Rotate,
+}
+
+struct Drag {
+    handle: Handle,
+    offset: Vec2,
+}
+
+/// Edits the bodies of a paused scene.
+///
+/// `radius` arguments are the size of the handles in world units, so that they
+/// keep the same size on screen whatever the zoom.
+pub struct Editor {
+    pub scene: Scene,
+    pub tool: Tool,
+    pub selected: Option<usize>,
+    pub selected_joint: Option<usize>,
+    /// The bodies already clicked with the joint tool.
+    pub joint_bodies: Vec<usize>,
+    drag: Option<Drag>,
+}
+
+fn hexagon() -> Vec<[f32; 2]> {
+    (0..6)
+        .map(|i| {
+            let angle = std::f32::consts::FRAC_PI_3 * i as f32;
+            [angle.cos(), angle.sin()]
+        })
+        .collect()
+}
+
+fn length(v: Vec2) -> f32 {
+    v.x.hypot(v.y)
+}
+
+/// Half of the size of the local bounding box of `desc`.
+fn half_extents(desc: &BodyDesc) -> Vec2 {
+    match &desc.shape {
+        ShapeDesc::Box { width } => Vec2::new(0.5 * width[0], 0.5 * width[1]),
+        ShapeDesc::Polygon { vertices } => vertices.iter().fold(Vec2::new(0.0, 0.0), |acc, v| {
+            Vec2::new(acc.x.max(v[0].abs()), acc.y.max(v[1].abs()))
+        }),
+    }
+}
+
+impl Editor {
+    pub fn new(scene: Scene) -> Editor {
+        Editor {
+            scene,
+            tool: Tool::Select,
+            selected: None,
+            selected_joint: None,
+            joint_bodies: Vec::new(),
+            drag: None,
+        }
+    }
+
+    /// The bodies of the scene, as they will be added to the world.
+    pub fn bodies(&self) -> Vec<Body> {
+        self.scene.bodies.iter().map(BodyDesc::build).collect()
+    }
+
+    /// The world space position of `handle` on the selected body.
+    pub fn handle_position(&self, handle: Handle, radius: f32) -> Option<Vec2> {
+        let desc = self.scene.bodies.get(self.selected?)?;
+        let body = desc.build();
+        let extents = half_extents(desc);
+        let local = match handle {
+            Handle::Move => Vec2::new(0.0, 0.0),
+            Handle::Resize => extents,
+            Handle::Rotate => Vec2::new(0.0, extents.y + 4.0 * radius),
+        };
+        Some(to_world(&body, local))
+    }
+
+    fn body_at(&self, point: Vec2) -> Option<usize> {
+        self.bodies().iter().rposition(|body| hit_test(body, point))
+    }
+
+    fn joint_at(&self, point: Vec2, radius: f32) -> Option<usize> {
+        self.scene
+            .joints
+            .iter()
+            .rposition(|joint| length(vec2(joint.anchor) - point) <= radius)
+    }
+
+    pub fn mouse_pressed(&mut self, point: Vec2, radius: f32) {
+        match self.tool {
+            Tool::AddJoint => {
+                if self.joint_bodies.len() < 2 {
+                    if let Some(index) = self.body_at(point) {
+                        if !self.joint_bodies.contains(&index) {
+                            self.joint_bodies.push(index);
+                        }
+                    }
+                    return;
+                }
+                self.scene.joints.push(JointDesc {
+                    bodies: [self.joint_bodies[0], self.joint_bodies[1]],
+                    anchor: to_array(point),
+                    softness: None,
+                    bias_factor: None,
+                    // A rigid pin, which does not depend on the time step. The
+                    // joint inspector turns it into a spring
+                    spring: None,
+                });
+                self.joint_bodies.clear();
+                self.selected = None;
+                self.selected_joint = Some(self.scene.joints.len() - 1);
+                self.tool = Tool::Select;
+            }
+            Tool::AddBox | Tool::AddPolygon => {
+                let shape = if self.tool == Tool::AddBox {
+                    ShapeDesc::Box { width: [1.0, 1.0] }
+                } else {
+                    ShapeDesc::Polygon {
+                        vertices: hexagon(),
+                    }
+                };
+                self.scene.bodies.push(BodyDesc {
+                    shape,
+                    mass: Some(10.0),
+                    position: to_array(point),
+                    rotation: 0.0,
+                    friction: Some(0.2),
+                    velocity: [0.0, 0.0],
+                    angular_velocity: 0.0,
+                    position_jitter: [0.0, 0.0],
+                    key: None,
+                });
+                self.selected = Some(self.scene.bodies.len() - 1);
+                self.selected_joint = None;
+                self.tool = Tool::Select;
+            }
+            Tool::Select => {
+                for handle in [Handle::Resize, Handle::Rotate] {
+                    let on_handle = self
+                        .handle_position(handle, radius)
+                        .is_some_and(|position| length(position - point) <= radius);
+                    if on_handle {
+                        self.drag = Some(Drag {
+                            handle,
+                            offset: Vec2::new(0.0, 0.0),
+                        });
+                        return;
+                    }
+                }
+                self.selected_joint = self.joint_at(point, radius);
+                if self.selected_joint.is_some() {
+                    self.selected = None;
+                    return;
+                }
+                self.selected = self.body_at(point);
+                self.drag = self.selected.map(|index| Drag {
+                    handle: Handle::Move,
+                    offset: vec2(self.scene.bodies[index].position) - point,
+                });
+            }
+        }
+    }
+
+    pub fn mouse_dragged(&mut self, point: Vec2) {
+        let (Some(drag), Some(index)) = (&self.drag, self.selected) else {
+            return;
+        };
+        let desc = &mut self.scene.bodies[index];
+        match drag.handle {
+            Handle::Move => desc.position = to_array(point + drag.offset),
+            Handle::Resize => {
+                let local = to_local(&desc.build(), point);
+                let extents = half_extents(desc);
+                match &mut desc.shape {
+                    ShapeDesc::Box { width } => {
+                        width[0] = (2.0 * local.x.abs()).max(MIN_SIZE);
+                        width[1] = (2.0 * local.y.abs()).max(MIN_SIZE);
+                    }
+                    ShapeDesc::Polygon { vertices } => {
+                        let factor = length(local).max(MIN_SIZE) / length(extents);
+                        for vertex in vertices.iter_mut() {
+                            vertex[0] *= factor;
+                            vertex[1] *= factor;
+                        }
+                    }
+                }
+            }
+            Handle::Rotate => {
+                let direction = point - vec2(desc.position);
+                desc.rotation = direction.y.atan2(direction.x) - std::f32::consts::FRAC_PI_2;
+            }
+        }
+    }
+
+    pub fn mouse_released(&mut self) {
+        self.drag = None;
+    }
+
+    /// Removes the selected joint, or the selected body and the joints
+    /// attached to it.
+    pub fn delete_selected(&mut self) {
+        if let Some(index) = self.selected_joint.take() {
+            self.scene.joints.remove(index);
+            return;
+        }
+        let Some(index) = self.selected.take() else {
+            return;
+        };
+        self.scene.bodies.remove(index);
+        self.scene
+            .joints
+            .retain(|joint| !joint.bodies.contains(&index));
+        for joint in self.scene.joints.iter_mut() {
+            for body in joint.bodies.iter_mut() {
+                if *body > index {
+                    *body -= 1;
+                }
+            }
+        }
+        // The bodies already picked for a new joint follow the same shift
+        self.joint_bodies.retain(|&body| body != index);
+        for body in self.joint_bodies.iter_mut() {
+            if *body > index {
+                *body -= 1;
+            }
+        }
+    }
+
+    pub fn tools_ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut self.tool, Tool::Select, "Select");
+            ui.selectable_value(&mut self.tool, Tool::AddBox, "Add box");
+            ui.selectable_value(&mut self.tool, Tool::AddPolygon, "Add polygon");
+            ui.selectable_value(&mut self.tool, Tool::AddJoint, "Add joint");
+        });
+        if self.tool != Tool::AddJoint {
+            self.joint_bodies.clear();
+            return;
+        }
+        ui.label(match self.joint_bodies.len() {
+            0 => "Click on the first body.",
+            1 => "Click on the second body.",
+            _ => "Click on the anchor point.",
+        });
+    }
+
+    fn joint_inspector_ui(&mut self, ui: &mut egui::Ui, index: usize) {
+        let joint = &mut self.scene.joints[index];
+        egui::Grid::new("joint_inspector").show(ui, |ui| {
+            ui.label("Bodies:");
+            ui.label(format!("{} and {}", joint.bodies[0], joint.bodies[1]));
+            ui.end_row();
+
+            ui.label("Anchor:");
+            ui.horizontal(|ui| {
+                ui.add(egui::DragValue::new(&mut joint.anchor[0]).speed(0.05));
+                ui.add(egui::DragValue::new(&mut joint.anchor[1]).speed(0.05));
+            });
+            ui.end_row();
+
+            ui.label("Spring:");
+            let mut is_spring = joint.spring.is_some();
+            if ui
+                .checkbox(&mut is_spring, "frequency and damping")
+                .changed()
+            {
+                joint.spring = is_spring.then(|| SpringConfig::new(2.0, 0.7, 10.0));
+            }
+            ui.end_row();
+
+            if let Some(spring) = &mut joint.spring {
+                for (label, value) in [
+                    ("Frequency (Hz):", &mut spring.frequency_hz),
+                    ("Damping ratio:", &mut spring.damping_ratio),
+                    ("Mass:", &mut spring.mass),
+                ] {
+                    ui.label(label);
+                    ui.add(
+                        egui::DragValue::new(value)
+                            .speed(0.01)
+                            .clamp_range(0.01..=f32::MAX),
+                    );
+                    ui.end_row();
+                }
+                return;
+            }
+
+            for (label, value, default) in [
+                ("Softness:", &mut joint.softness, 0.0),
+                ("Bias factor:", &mut joint.bias_factor, 0.2),
+            ] {
+                ui.label(label);
+                ui.horizontal(|ui| {
+                    let mut is_default = value.is_none();
+                    if ui.checkbox(&mut is_default, "default").changed() {
+                        *value = if is_default { None } else { Some(default) };
+                    }
+                    if let Some(value) = value {
+                        ui.add(
+                            egui::DragValue::new(value)
+                                .speed(0.001)
+                                .clamp_range(0.0..=f32::MAX),
+                        );
+                    }
+                });
+                ui.end_row();
+            }
+        });
+        if ui.button("Delete joint").clicked() {
+            self.delete_selected();
+        }
+    }
+
+    pub fn inspector_ui(&mut self, ui: &mut egui::Ui) {
+        if let Some(index) = self.selected_joint {
+            self.joint_inspector_ui(ui, index);
+            return;
+        }
+        let Some(index) = self.selected else {
+            ui.label("Click on a body or a joint anchor to select it.");
+            return;
+        };
+        let desc = &mut self.scene.bodies[index];
+        egui::Grid::new("inspector").show(ui, |ui| {
+            match &mut desc.shape {
+                ShapeDesc::Box { width } => {
+                    ui.label("Width:");
+                    ui.horizontal(|ui| {
+                        ui.add(
+                            egui::DragValue::new(&mut width[0])
+                                .speed(0.05)
+                                .clamp_range(MIN_SIZE..=f32::MAX),
+                        );
+                        ui.add(
+                            egui::DragValue::new(&mut width[1])
+                                .speed(0.05)
+                                .clamp_range(MIN_SIZE..=f32::MAX),
+                        );
+                    });
+                }
+                ShapeDesc::Polygon { vertices } => {
+                    ui.label("Vertices:");
+                    ui.label(vertices.len().to_string());
+                }
+            }
+            ui.end_row();
+
+            ui.label("Position:");
+            ui.horizontal(|ui| {
+                ui.add(egui::DragValue::new(&mut desc.position[0]).speed(0.05));
+                ui.add(egui::DragValue::new(&mut desc.position[1]).speed(0.05));
+            });
+            ui.end_row();
+
+            ui.label("Rotation:");
+            ui.drag_angle(&mut desc.rotation);
+            ui.end_row();
+
+            ui.label("Mass:");
+            ui.horizontal(|ui| {
+                let mut is_static = desc.mass.is_none();
+                if ui.checkbox(&mut is_static, "static").changed() {
+                    desc.mass = if is_static { None } else { Some(10.0) };
+                }
+                if let Some(mass) = &mut desc.mass {
+                    ui.add(
+                        egui::DragValue::new(mass)
+                            .speed(0.1)
+                            .clamp_range(0.01..=f32::MAX),
+                    );
+                }
+            });
+            ui.end_row();
+
+            ui.label("Friction:");
+            let mut friction = desc.friction.unwrap_or_else(|| desc.build().friction);
+            if ui
+                .add(
+                    egui::DragValue::new(&mut friction)
+                        .speed(0.01)
+                        .clamp_range(0.0..=f32::MAX),
+                )
+                .changed()
+            {
+                desc.friction = Some(friction);
+            }
+            ui.end_row();
+        });
+        if ui.button("Delete body").clicked() {
+            self.delete_selected();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An editor on a scene of `bodies` boxes linked by `joints`.
+    fn editor(bodies: usize, joints: &[[usize; 2]]) -> Editor {
+        let scene = serde_json::json!({
+            "name": "Test",
+            "bodies": (0..bodies)
+                .map(|i| serde_json::json!({
+                    "shape": "box",
+                    "width": [1.0, 1.0],
+                    "mass": 1.0,
+                    "position": [2.0 * i as f32, 0.0],
+                }))
+                .collect::<Vec<_>>(),
+            "joints": joints
+                .iter()
+                .map(|bodies| serde_json::json!({ "bodies": bodies, "anchor": [0.0, 0.0] }))
+                .collect::<Vec<_>>(),
+        });
+        Editor::new(serde_json::from_value(scene).unwrap())
+    }
+
+    fn joints(editor: &Editor) -> Vec<[usize; 2]> {
+        editor
+            .scene
+            .joints
+            .iter()
+            .map(|joint| joint.bodies)
+            .collect()
+    }
+
+    fn delete_body(editor: &mut Editor, index: usize) {
+        editor.selected = Some(index);
+        editor.delete_selected();
+    }
+
+    #[test]
+    fn deleting_a_body_deletes_its_joints() {
+        let mut editor = editor(4, &[[0, 1], [2, 3], [1, 3]]);
+        delete_body(&mut editor, 1);
+        assert_eq!(editor.scene.bodies.len(), 3);
+        assert_eq!(joints(&editor), vec![[1, 2]]);
+    }
+
+    #[test]
+    fn deleting_a_body_shifts_the_bodies_after_it() {
+        let mut editor = editor(4, &[[1, 2], [3, 2]]);
+        delete_body(&mut editor, 0);
+        assert_eq!(joints(&editor), vec![[0, 1], [2, 1]]);
+    }
+
+    #[test]
+    fn deleting_a_body_updates_the_pending_joint() {
+        let mut editor = editor(4, &[]);
+        editor.joint_bodies = vec![0, 2];
+        delete_body(&mut editor, 1);
+        assert_eq!(editor.joint_bodies, vec![0, 1]);
+
+        editor.joint_bodies = vec![2];
+        delete_body(&mut editor, 2);
+        assert!(editor.joint_bodies.is_empty());
+    }
+}
@@ -0,0 +1,38 @@
+/// Saves `contents` as a file named `file_name`.
+///
+/// In the browser this triggers a download, on native the file is written to
+/// the current working directory.
+#[cfg(target_arch = "wasm32")]
+pub fn save_text_file(file_name: &str, contents: &str) -> Result<(), String> {
+    use wasm_bindgen::{JsCast, JsValue};
+
+    let js_error = |e: JsValue| format!("{:?}", e);
+
+    let parts = js_sys::Array::of1(&JsValue::from_str(contents));
+    let options = web_sys::BlobPropertyBag::new();
+    options.set_type("application/json");
+    let blob =
+        web_sys::Blob::new_with_str_sequence_and_options(&parts, &options).map_err(js_error)?;
+    let url = web_sys::Url::create_object_url_with_blob(&blob).map_err(js_error)?;
+
+    let document = web_sys::window()
+        .and_then(|window| window.document())
+        .ok_or("No document to download the file from")?;
+    let anchor: web_sys::HtmlAnchorElement = document
+        .create_element("a")
+        .map_err(js_error)?
+        .dyn_into()
+        .map_err(|_| "Could not create a download link")?;
+    anchor.set_href(&url);
+    anchor.set_download(file_name);
+    anchor.click();
+
+    web_sys::Url::revoke_object_url(&url).map_err(js_error)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn save_text_file(file_name: &str, contents: &str) -> Result<(), String> {
+    std::fs::write(file_name, contents).map_err(|e| e.to_string())?;
+    println!("Saved {}", file_name);
+    Ok(())
+}
@@ -0,0 +1,113 @@
+use std::io::{self, Write};
+
+use rand::SeedableRng;
+use rand_pcg::Pcg32;
+use sylt_2d::math_utils::Vec2;
+use sylt_2d::world::World;
+
+use crate::scene::{to_array, Scene, BUILTIN_SCENES};
+
+/// The parameters of a simulation run without a window.
+#[derive(Debug, Clone, Copy)]
+pub struct RunConfig {
+    pub steps: u32,
+    pub time_step: f32,
+    /// Overrides the iteration count of the scene.
+    pub iterations: Option<u32>,
+    /// Seeds the random position jitter of the scene.
+    pub seed: u64,
+}
+
+impl Default for RunConfig {
+    fn default() -> Self {
+        RunConfig {
+            steps: 600,
+            time_step: 1.0 / 60.0,
+            iterations: None,
+            seed: 0,
+        }
+    }
+}
+
+/// The state of a body after a step.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BodyState {
+    pub position: [f32; 2],
+    pub rotation: f32,
+    pub velocity: [f32; 2],
+    pub angular_velocity: f32,
+}
+
+impl BodyState {
+    pub const CSV_HEADER: &'static str = "step,body,x,y,rotation,vx,vy,angular_velocity";
+
+    pub fn of_world(world: &World) -> Vec<BodyState> {
+        world
+            .iter_bodies()
+            .map(|body| BodyState {
+                position: to_array(body.position),
+                rotation: body.rotation,
+                velocity: to_array(body.velocity),
+                angular_velocity: body.angular_velocity,
+            })
+            .collect()
+    }
+
+    pub fn write_csv(&self, out: &mut impl Write, step: u32, body: usize) -> io::Result<()> {
+        writeln!(
+            out,
+            "{},{},{},{},{},{},{},{}",
+            step,
+            body,
+            self.position[0],
+            self.position[1],
+            self.rotation,
+            self.velocity[0],
+            self.velocity[1],
+            self.angular_velocity
+        )
+    }
+}
+
+/// Finds the index of a built-in scene by its number starting at 1, its file
+/// name with or without the `.json` extension, or its name ignoring case.
+pub fn find_builtin_scene_index(query: &str) -> Option<usize> {
+    let index = match query.parse::<usize>() {
+        Ok(number) => number.checked_sub(1)?,
+        Err(_) => BUILTIN_SCENES.iter().position(|(file_name, source)| {
+            *file_name == query
+                || file_name.strip_suffix(".json") == Some(query)
+                || Scene::from_json(source)
+                    .is_ok_and(|scene| scene.name.eq_ignore_ascii_case(query))
+        })?,
+    };
+    (index < BUILTIN_SCENES.len()).then_some(index)
+}
+
+/// Finds a built-in scene as `find_builtin_scene_index` does.
+pub fn find_builtin_scene(query: &str) -> Option<Scene> {
+    let (_, source) = BUILTIN_SCENES.get(find_builtin_scene_index(query)?)?;
+    Scene::from_json(source).ok()
+}
+
+/// Builds `scene` and steps it `config.steps` times, calling `on_step` with the
+/// step number and the world after each step. Returns the final world.
+pub fn run(
+    scene: &Scene,
+    config: &RunConfig,
+    mut on_step: impl FnMut(u32, &World),
+) -> Result<World, String> {
+    let mut scene = scene.clone();
+    if let Some(iterations) = config.iterations {
+        scene.iterations = iterations;
+    }
+    let mut world = World::new(Vec2::new(0.0, -10.0), scene.iterations);
+    let mut rng = Pcg32::seed_from_u64(config.seed);
+    scene.load_into(&mut world, config.time_step, &mut rng)?;
+
+    for step in 1..=config.steps {
+        world.step(config.time_step).map_err(|e| e.to_string())?;
+        on_step(step, &world);
+    }
+    Ok(world)
+}
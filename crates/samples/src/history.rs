@@ -0,0 +1,229 @@
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+use rand_pcg::Pcg32;
+use sylt_2d::arbiter::{Arbiter, ArbiterKey};
+use sylt_2d::body::Body;
+use sylt_2d::math_utils::Vec2;
+use sylt_2d::world::World;
+
+/// About ten seconds of simulation at 60 Hz.
+pub const HISTORY_CAPACITY: usize = 600;
+
+#[derive(Debug, Clone, Copy)]
+struct BodyState {
+    position: Vec2,
+    rotation: f32,
+    velocity: Vec2,
+    angular_velocity: f32,
+    force: Vec2,
+    torque: f32,
+    friction: f32,
+}
+
+/// The state of a world after a step, including the accumulated impulses used
+/// to warm start the next step, and of the random numbers drawn from then on.
+struct Snapshot {
+    step: u64,
+    bodies: Vec<(Rc<RefCell<Body>>, BodyState)>,
+    arbiters: Vec<(ArbiterKey, Arbiter)>,
+    joint_impulses: Vec<Vec2>,
+    rng: Pcg32,
+}
+
+impl Snapshot {
+    fn capture(world: &World, rng: &Pcg32, step: u64) -> Snapshot {
+        let bodies = world
+            .bodies
+            .iter()
+            .map(|body| {
+                let state = {
+                    let body = body.borrow();
+                    BodyState {
+                        position: body.position,
+                        rotation: body.rotation,
+                        velocity: body.velocity,
+                        angular_velocity: body.angular_velocity,
+                        force: body.force,
+                        torque: body.torque,
+                        friction: body.friction,
+                    }
+                };
+                (body.clone(), state)
+            })
+            .collect();
+        Snapshot {
+            step,
+            bodies,
+            arbiters: world
+                .arbiters
+                .iter()
+                .map(|(key, arbiter)| (key.clone(), arbiter.clone()))
+                .collect(),
+            joint_impulses: world.joints.iter().map(|joint| joint.p).collect(),
+            rng: rng.clone(),
+        }
+    }
+
+    fn restore(&self, world: &mut World, rng: &mut Pcg32) {
+        // Bodies added after the snapshot, e.g. bombs, are removed and the ones
+        // removed since are added back.
+        world.bodies = self.bodies.iter().map(|(body, _)| body.clone()).collect();
+        for (body, state) in self.bodies.iter() {
+            let mut body = body.borrow_mut();
+            body.position = state.position;
+            body.rotation = state.rotation;
+            body.velocity = state.velocity;
+            body.angular_velocity = state.angular_velocity;
+            body.force = state.force;
+            body.torque = state.torque;
+            body.friction = state.friction;
+        }
+        world.arbiters = self.arbiters.iter().cloned().collect();
+        for (joint, impulse) in world.joints.iter_mut().zip(self.joint_impulses.iter()) {
+            joint.p = *impulse;
+        }
+        *rng = self.rng.clone();
+    }
+}
+
+/// A bounded record of the states a world went through, to jump back and
+/// forward in time.
+pub struct History {
+    snapshots: VecDeque<Snapshot>,
+    capacity: usize,
+    /// The snapshot restored last, while looking at the past.
+    cursor: Option<usize>,
+}
+
+impl Default for History {
+    fn default() -> Self {
+        History::new(HISTORY_CAPACITY)
+    }
+}
+
+impl History {
+    pub fn new(capacity: usize) -> History {
+        History {
+            snapshots: VecDeque::with_capacity(capacity),
+            capacity,
+            cursor: None,
+        }
+    }
+
+    /// The index of the latest snapshot.
+    pub fn last_index(&self) -> usize {
+        self.snapshots.len().saturating_sub(1)
+    }
+
+    /// The index of the snapshot matching the state of the world.
+    pub fn position(&self) -> usize {
+        self.cursor.unwrap_or(self.last_index())
+    }
+
+    pub fn clear(&mut self) {
+        self.snapshots.clear();
+        self.cursor = None;
+    }
+
+    /// Records the state of `world` and `rng` after `step`. When the simulation
+    /// resumes from a past state, the snapshots after that state are dropped.
+    pub fn record(&mut self, world: &World, rng: &Pcg32, step: u64) {
+        if let Some(cursor) = self.cursor.take() {
+            self.snapshots.truncate(cursor + 1);
+        }
+        if self.snapshots.len() == self.capacity {
+            self.snapshots.pop_front();
+        }
+        self.snapshots
+            .push_back(Snapshot::capture(world, rng, step));
+    }
+
+    /// Puts `world` and `rng` back in the state of the snapshot at `index`,
+    /// returning the step index of that state.
+    pub fn restore(&mut self, index: usize, world: &mut World, rng: &mut Pcg32) -> Option<u64> {
+        let snapshot = self.snapshots.get(index)?;
+        snapshot.restore(world, rng);
+        self.cursor = Some(index);
+        Some(snapshot.step)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::{Rng, SeedableRng};
+
+    /// A world with one body at height `y`.
+    fn world_at(y: f32) -> World {
+        let mut world = World::new(Vec2::new(0.0, -10.0), 10);
+        world.add_body(Body::new(Vec2::new(1.0, 1.0), 1.0));
+        move_to(&world, y);
+        world
+    }
+
+    fn move_to(world: &World, y: f32) {
+        world.bodies[0].borrow_mut().position = Vec2::new(0.0, y);
+    }
+
+    fn height(world: &World) -> f32 {
+        world.bodies[0].borrow().position.y
+    }
+
+    /// Records the world at the heights `0, 1, ..` for steps `0, 1, ..`.
+    fn history_of(steps: u64, capacity: usize) -> (History, World) {
+        let mut history = History::new(capacity);
+        let world = world_at(0.0);
+        let rng = Pcg32::seed_from_u64(0);
+        for step in 0..steps {
+            move_to(&world, step as f32);
+            history.record(&world, &rng, step);
+        }
+        (history, world)
+    }
+
+    #[test]
+    fn restores_the_state_at_an_index() {
+        let (mut history, mut world) = history_of(5, 10);
+        let mut rng = Pcg32::seed_from_u64(0);
+        assert_eq!(history.restore(2, &mut world, &mut rng), Some(2));
+        assert_eq!(height(&world), 2.0);
+        assert_eq!(history.position(), 2);
+        assert_eq!(history.restore(5, &mut world, &mut rng), None);
+    }
+
+    #[test]
+    fn restores_the_random_numbers() {
+        let mut history = History::new(10);
+        let mut world = world_at(0.0);
+        let mut rng = Pcg32::seed_from_u64(7);
+        history.record(&world, &rng, 0);
+        let drawn: f32 = rng.gen();
+        history.restore(0, &mut world, &mut rng);
+        assert_eq!(rng.gen::<f32>(), drawn);
+    }
+
+    #[test]
+    fn drops_the_oldest_snapshot_when_full() {
+        let (mut history, mut world) = history_of(5, 3);
+        let mut rng = Pcg32::seed_from_u64(0);
+        assert_eq!(history.last_index(), 2);
+        assert_eq!(history.restore(0, &mut world, &mut rng), Some(2));
+        assert_eq!(height(&world), 2.0);
+    }
+
+    #[test]
+    fn recording_after_a_restore_drops_the_future() {
+        let (mut history, mut world) = history_of(5, 10);
+        let mut rng = Pcg32::seed_from_u64(0);
+        history.restore(1, &mut world, &mut rng);
+        move_to(&world, 10.0);
+        history.record(&world, &rng, 2);
+        assert_eq!(history.last_index(), 2);
+        assert_eq!(history.position(), 2);
+        assert_eq!(history.restore(2, &mut world, &mut rng), Some(2));
+        assert_eq!(height(&world), 10.0);
+    }
+}
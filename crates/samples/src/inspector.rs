@@ -0,0 +1,157 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use nannou_egui::egui;
+use serde::{Deserialize, Serialize};
+use sylt_2d::body::{Body, Shape};
+use sylt_2d::world::World;
+
+use crate::picking::is_static;
+use crate::scene::{to_array, vec2};
+
+/// The fields of a live body that can be edited from the inspector.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BodyEdit {
+    pub position: [f32; 2],
+    pub rotation: f32,
+    pub velocity: [f32; 2],
+    pub angular_velocity: f32,
+    pub friction: f32,
+}
+
+impl BodyEdit {
+    pub fn of(body: &Body) -> BodyEdit {
+        BodyEdit {
+            position: to_array(body.position),
+            rotation: body.rotation,
+            velocity: to_array(body.velocity),
+            angular_velocity: body.angular_velocity,
+            friction: body.friction,
+        }
+    }
+
+    pub fn apply(&self, body: &mut Body) {
+        body.position = vec2(self.position);
+        body.rotation = self.rotation;
+        body.velocity = vec2(self.velocity);
+        body.angular_velocity = self.angular_velocity;
+        body.friction = self.friction;
+    }
+}
+
+fn format_infinite(value: f32) -> String {
+    if value == f32::MAX {
+        "infinite".to_string()
+    } else {
+        format!("{:.4}", value)
+    }
+}
+
+fn vector_ui(ui: &mut egui::Ui, value: &mut [f32; 2], speed: f64) -> bool {
+    ui.horizontal(|ui| {
+        let x = ui.add(egui::DragValue::new(&mut value[0]).speed(speed));
+        let y = ui.add(egui::DragValue::new(&mut value[1]).speed(speed));
+        x.changed() || y.changed()
+    })
+    .inner
+}
+
+/// Shows the state of the body at `index` in `world`. Returns the edited
+/// state when a field was changed, to be applied on the next update.
+pub fn body_inspector_ui(ui: &mut egui::Ui, world: &World, index: usize) -> Option<BodyEdit> {
+    let Some(body_ref) = world.bodies.get(index) else {
+        ui.label("The body is no longer in the world.");
+        return None;
+    };
+    let body = body_ref.borrow();
+    let mut edit = BodyEdit::of(&body);
+    let mut changed = false;
+
+    egui::Grid::new("body_inspector").show(ui, |ui| {
+        ui.label("Body:");
+        ui.label(index.to_string());
+        ui.end_row();
+
+        ui.label("Shape:");
+        match body.shape {
+            Shape::Box => {
+                ui.label(format!("box {:.3} x {:.3}", body.width.x, body.width.y));
+            }
+            Shape::ConvexPolygon => {
+                let vertices = body.get_polygon().get_vertices();
+                ui.vertical(|ui| {
+                    ui.label(format!("polygon, {} vertices", vertices.len()));
+                    for vertex in vertices {
+                        ui.label(format!("({:.3}, {:.3})", vertex.x, vertex.y));
+                    }
+                });
+            }
+        }
+        ui.end_row();
+
+        ui.label("Mass:");
+        ui.label(format_infinite(body.mass));
+        ui.end_row();
+
+        ui.label("Inverse mass:");
+        ui.label(format!("{:.4}", body.inv_mass));
+        ui.end_row();
+
+        ui.label("Inertia:");
+        ui.label(format_infinite(body.inertia));
+        ui.end_row();
+
+        ui.label("Position:");
+        changed |= vector_ui(ui, &mut edit.position, 0.05);
+        ui.end_row();
+
+        ui.label("Rotation:");
+        changed |= ui.drag_angle(&mut edit.rotation).changed();
+        ui.end_row();
+
+        if !is_static(&body) {
+            ui.label("Velocity:");
+            changed |= vector_ui(ui, &mut edit.velocity, 0.1);
+            ui.end_row();
+
+            ui.label("Angular velocity:");
+            changed |= ui
+                .add(egui::DragValue::new(&mut edit.angular_velocity).speed(0.1))
+                .changed();
+            ui.end_row();
+        }
+
+        ui.label("Friction:");
+        changed |= ui
+            .add(
+                egui::DragValue::new(&mut edit.friction)
+                    .speed(0.01)
+                    .clamp_range(0.0..=f32::MAX),
+            )
+            .changed();
+        ui.end_row();
+    });
+
+    ui.label("Arbiters:");
+    let index_of =
+        |other: &Rc<RefCell<Body>>| world.bodies.iter().position(|body| Rc::ptr_eq(body, other));
+    let mut any = false;
+    for arbiter in world.arbiters.values() {
+        let other = if Rc::ptr_eq(&arbiter.body_1, body_ref) {
+            &arbiter.body_2
+        } else if Rc::ptr_eq(&arbiter.body_2, body_ref) {
+            &arbiter.body_1
+        } else {
+            continue;
+        };
+        let contacts = arbiter.contacts.iter().flatten().count();
+        let other = index_of(other).map_or("?".to_string(), |other| other.to_string());
+        ui.label(format!("with body {}: {} contacts", other, contacts));
+        any = true;
+    }
+    if !any {
+        ui.label("none");
+    }
+
+    changed.then_some(edit)
+}
@@ -0,0 +1,204 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use nannou::color::{IntoLinSrgba, LinSrgba};
+use nannou::prelude::*;
+use nannou_egui::egui;
+use sylt_2d::body::{Body, Shape};
+use sylt_2d::math_utils::{Mat2x2, Vec2};
+use sylt_2d::world::World;
+
+use crate::timestep::Pose;
+
+/// Length of the normal arrows per unit of normal impulse.
+const IMPULSE_SCALE: f32 = 0.5;
+/// Length of the velocity arrows per unit of velocity.
+const VELOCITY_SCALE: f32 = 0.2;
+
+/// Debug information drawn on top of the bodies, each switchable on its own.
+#[derive(Debug, Clone, Copy)]
+pub struct DebugLayers {
+    pub contacts: bool,
+    /// Gives the contacts of each arbiter their own colour.
+    pub color_by_arbiter: bool,
+    pub normals: bool,
+    /// Scales the normals by the accumulated normal impulse.
+    pub scale_normals: bool,
+    pub friction_impulses: bool,
+    pub separations: bool,
+    pub arbiter_pairs: bool,
+    pub aabbs: bool,
+    pub centers_of_mass: bool,
+    pub velocities: bool,
+}
+
+impl Default for DebugLayers {
+    fn default() -> Self {
+        DebugLayers {
+            contacts: true,
+            color_by_arbiter: false,
+            normals: true,
+            scale_normals: false,
+            friction_impulses: false,
+            separations: false,
+            arbiter_pairs: false,
+            aabbs: false,
+            centers_of_mass: false,
+            velocities: false,
+        }
+    }
+}
+
+impl DebugLayers {
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.checkbox(&mut self.contacts, "Contact points");
+        ui.checkbox(&mut self.color_by_arbiter, "Colour contacts by arbiter");
+        ui.checkbox(&mut self.normals, "Contact normals");
+        ui.checkbox(&mut self.scale_normals, "Scale normals by impulse");
+        ui.checkbox(&mut self.friction_impulses, "Friction impulses");
+        ui.checkbox(&mut self.separations, "Separation labels");
+        ui.checkbox(&mut self.arbiter_pairs, "Arbiter pairs");
+        ui.checkbox(&mut self.aabbs, "Bounding boxes");
+        ui.checkbox(&mut self.centers_of_mass, "Centres of mass");
+        ui.checkbox(&mut self.velocities, "Velocities");
+    }
+}
+
+fn index_of(world: &World, body: &Rc<RefCell<Body>>) -> Option<usize> {
+    world
+        .bodies
+        .iter()
+        .position(|other| Rc::ptr_eq(other, body))
+}
+
+/// A colour that stays the same for the arbiter of a pair of bodies.
+fn arbiter_color(index_1: usize, index_2: usize) -> LinSrgba {
+    let hash = (index_1 * 31 + index_2) as f32 * 0.618_034;
+    hsl(hash.fract(), 0.8, 0.6).into_lin_srgba()
+}
+
+/// The corners of the bounding box of `body` at `pose`.
+pub fn aabb(body: &Body, pose: &Pose) -> (Vec2, Vec2) {
+    let vertices = match body.shape {
+        Shape::Box => {
+            let h = body.width * 0.5;
+            vec![
+                Vec2::new(-h.x, -h.y),
+                Vec2::new(h.x, -h.y),
+                Vec2::new(h.x, h.y),
+                Vec2::new(-h.x, h.y),
+            ]
+        }
+        Shape::ConvexPolygon => body.get_polygon().get_vertices(),
+    };
+    let rotation = Mat2x2::new_from_angle(pose.rotation);
+    let mut min = Vec2::new(f32::MAX, f32::MAX);
+    let mut max = Vec2::new(f32::MIN, f32::MIN);
+    for vertex in vertices {
+        let p = pose.position + rotation * vertex;
+        min = Vec2::new(min.x.min(p.x), min.y.min(p.y));
+        max = Vec2::new(max.x.max(p.x), max.y.max(p.y));
+    }
+    (min, max)
+}
+
+/// Draws the layers attached to a single body.
+pub fn draw_body_layers(draw: &Draw, body: &Body, pose: &Pose, layers: &DebugLayers) {
+    if layers.aabbs {
+        let (min, max) = aabb(body, pose);
+        draw.rect()
+            .x_y(0.5 * (min.x + max.x), 0.5 * (min.y + max.y))
+            .w_h(max.x - min.x, max.y - min.y)
+            .no_fill()
+            .stroke(YELLOW)
+            .stroke_weight(0.03);
+    }
+    if layers.centers_of_mass {
+        draw.ellipse()
+            .x_y(pose.position.x, pose.position.y)
+            .radius(0.12)
+            .color(BLACK);
+    }
+    if layers.velocities && (body.velocity.x != 0.0 || body.velocity.y != 0.0) {
+        let end = pose.position + body.velocity * VELOCITY_SCALE;
+        draw.arrow()
+            .start(pt2(pose.position.x, pose.position.y))
+            .end(pt2(end.x, end.y))
+            .weight(0.05)
+            .color(DEEPSKYBLUE);
+    }
+}
+
+/// Draws the layers attached to the arbiters of `world`.
+///
+/// `poses` holds the drawn pose of each body. The separation labels are drawn
+/// with `screen`, an untransformed `Draw`, at the point given by `to_screen`
+/// so the text keeps its size whatever the zoom.
+pub fn draw_arbiter_layers(
+    draw: &Draw,
+    screen: &Draw,
+    to_screen: impl Fn(Vec2) -> Point2,
+    world: &World,
+    poses: &[Pose],
+    layers: &DebugLayers,
+    contact_color: Srgb<u8>,
+) {
+    for (_, arbiter) in world.arbiters.iter() {
+        let index_1 = index_of(world, &arbiter.body_1);
+        let index_2 = index_of(world, &arbiter.body_2);
+        let color = match (index_1, index_2) {
+            (Some(index_1), Some(index_2)) if layers.color_by_arbiter => {
+                arbiter_color(index_1, index_2)
+            }
+            _ => contact_color.into_lin_srgba(),
+        };
+
+        if layers.arbiter_pairs {
+            let pose_1 = index_1.and_then(|index| poses.get(index));
+            let pose_2 = index_2.and_then(|index| poses.get(index));
+            if let (Some(pose_1), Some(pose_2)) = (pose_1, pose_2) {
+                draw.line()
+                    .start(pt2(pose_1.position.x, pose_1.position.y))
+                    .end(pt2(pose_2.position.x, pose_2.position.y))
+                    .weight(0.03)
+                    .color(color);
+            }
+        }
+
+        for contact in arbiter.contacts.iter().flatten() {
+            let position = pt2(contact.position.x, contact.position.y);
+            if layers.contacts {
+                draw.ellipse().xy(position).radius(0.1).color(color);
+            }
+            if layers.normals {
+                let length = if layers.scale_normals {
+                    contact.pn * IMPULSE_SCALE
+                } else {
+                    1.0
+                };
+                let end = contact.position + contact.normal * length;
+                draw.arrow()
+                    .start(position)
+                    .end(pt2(end.x, end.y))
+                    .weight(0.05)
+                    .color(LIGHTSALMON);
+            }
+            if layers.friction_impulses {
+                let tangent = Vec2::new(contact.normal.y, -contact.normal.x);
+                let end = contact.position + tangent * (contact.pt * IMPULSE_SCALE);
+                draw.arrow()
+                    .start(position)
+                    .end(pt2(end.x, end.y))
+                    .weight(0.05)
+                    .color(MEDIUMSEAGREEN);
+            }
+            if layers.separations {
+                screen
+                    .text(&format!("{:.3}", contact.separation))
+                    .xy(to_screen(contact.position) + vec2(0.0, 10.0))
+                    .font_size(11)
+                    .color(WHITE);
+            }
+        }
+    }
+}
@@ -1,13 +1,32 @@
-use async_std::task::block_on;
-use wasm_bindgen::prelude::*;
+#[cfg(all(feature = "gui", target_arch = "wasm32"))]
+pub mod api;
+#[cfg(feature = "gui")]
+mod colors;
+pub mod diagnostics;
+#[cfg(feature = "gui")]
+mod editor;
+#[cfg(feature = "gui")]
+mod export;
+pub mod headless;
+#[cfg(feature = "gui")]
+mod history;
+#[cfg(feature = "gui")]
+mod inspector;
+#[cfg(feature = "gui")]
+mod layers;
+pub mod picking;
+#[cfg(feature = "gui")]
+mod plots;
+#[cfg(feature = "gui")]
+pub mod remote;
+#[cfg(feature = "gui")]
+mod replay;
+pub mod scene;
+#[cfg(feature = "gui")]
+pub mod sketch;
+pub mod spring;
+#[cfg(feature = "gui")]
+mod timestep;
 
-mod app;
-use app::run_app;
-mod sketch;
-
-#[wasm_bindgen]
-pub async fn main_web() {
-    block_on(async {
-        run_app().await;
-    });
-}
+#[cfg(feature = "standalone")]
+common::main_web!(sketch::create_model, sketch::update);
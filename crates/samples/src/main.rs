@@ -1,13 +1 @@
-use async_std::task::block_on;
-
-
-mod app;
-use app::run_app;
-mod sketch;
-
-fn main() {
-    block_on(async {
-        run_app().await;
-    });
-}
-
+common::main_native!(samples::sketch::create_model, samples::sketch::update);
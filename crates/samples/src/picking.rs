@@ -0,0 +1,85 @@
+use common::geometry::{cross, hit_test, rotate, to_local, to_world};
+use sylt_2d::body::Body;
+use sylt_2d::math_utils::Vec2;
+use sylt_2d::world::World;
+
+use crate::spring::SpringConfig;
+
+pub fn is_static(body: &Body) -> bool {
+    body.mass == f32::MAX
+}
+
+/// Returns the index of the top most dynamic body under `point`.
+pub fn pick_body(world: &World, point: Vec2) -> Option<usize> {
+    world
+        .iter_bodies()
+        .enumerate()
+        .filter(|(_, body)| !is_static(body) && hit_test(body, point))
+        .map(|(i, _)| i)
+        .last()
+}
+
+/// Returns the index of the top most body under `point`, static or not.
+pub fn body_under(world: &World, point: Vec2) -> Option<usize> {
+    world
+        .iter_bodies()
+        .enumerate()
+        .filter(|(_, body)| hit_test(body, point))
+        .map(|(i, _)| i)
+        .last()
+}
+
+/// A soft spring pulling a point of a body towards `target`.
+pub struct MouseJoint {
+    pub body_index: usize,
+    pub local_anchor: Vec2,
+    pub target: Vec2,
+    pub spring: SpringConfig,
+}
+
+impl MouseJoint {
+    /// Grabs the body at `body_index` at the world space `point`.
+    pub fn new(world: &World, body_index: usize, point: Vec2) -> MouseJoint {
+        let body = world.bodies[body_index].borrow();
+        MouseJoint {
+            body_index,
+            local_anchor: to_local(&body, point),
+            target: point,
+            spring: SpringConfig::new(5.0, 0.7, body.mass),
+        }
+    }
+
+    /// The world space position of the grabbed point.
+    pub fn anchor(&self, world: &World) -> Option<Vec2> {
+        let body = world.bodies.get(self.body_index)?.borrow();
+        Some(to_world(&body, self.local_anchor))
+    }
+
+    /// Sets the spring force on the body, to be integrated by the next `World::step`.
+    pub fn apply(&self, world: &World) {
+        let Some(body) = world.bodies.get(self.body_index) else {
+            return;
+        };
+        let mut body = body.borrow_mut();
+        let k = self.spring.stiffness();
+        let d = self.spring.damping();
+
+        let r = rotate(self.local_anchor, body.rotation);
+        let anchor = body.position + r;
+        let anchor_velocity =
+            body.velocity + Vec2::new(-body.angular_velocity * r.y, body.angular_velocity * r.x);
+        let force = (self.target - anchor) * k - anchor_velocity * d;
+
+        body.force = force;
+        body.torque = cross(r, force);
+    }
+
+    /// Removes the spring force from the body.
+    pub fn release(&self, world: &World) {
+        if let Some(body) = world.bodies.get(self.body_index) {
+            let mut body = body.borrow_mut();
+            body.force = Vec2::new(0.0, 0.0);
+            body.torque = 0.0;
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use std::collections::VecDeque;
+
+use egui_plot::{Legend, Line, Plot, PlotPoints};
+use nannou_egui::egui;
+
+use crate::diagnostics::Diagnostics;
+
+/// About ten seconds of simulation at 60 Hz.
+pub const PLOT_CAPACITY: usize = 600;
+
+/// The current time in milliseconds, for measuring how long a step takes.
+#[cfg(target_arch = "wasm32")]
+pub fn now_ms() -> f64 {
+    web_sys::window()
+        .and_then(|window| window.performance())
+        .map_or(0.0, |performance| performance.now())
+}
+
+/// The current time in milliseconds, for measuring how long a step takes.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn now_ms() -> f64 {
+    use std::sync::OnceLock;
+    use std::time::Instant;
+
+    static START: OnceLock<Instant> = OnceLock::new();
+    START.get_or_init(Instant::now).elapsed().as_secs_f64() * 1000.0
+}
+
+struct Sample {
+    step: u64,
+    diagnostics: Diagnostics,
+    step_time_ms: f64,
+}
+
+/// The diagnostics of the last steps, plotted over the step index.
+pub struct Plots {
+    samples: VecDeque<Sample>,
+    capacity: usize,
+}
+
+impl Default for Plots {
+    fn default() -> Self {
+        Plots::new(PLOT_CAPACITY)
+    }
+}
+
+impl Plots {
+    pub fn new(capacity: usize) -> Plots {
+        Plots {
+            samples: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Adds the sample of `step`. Samples from later steps, left over after
+    /// going back in time, are dropped.
+    pub fn record(&mut self, step: u64, diagnostics: Diagnostics, step_time_ms: f64) {
+        while self
+            .samples
+            .back()
+            .is_some_and(|sample| sample.step >= step)
+        {
+            self.samples.pop_back();
+        }
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(Sample {
+            step,
+            diagnostics,
+            step_time_ms,
+        });
+    }
+
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+
+    fn line(&self, name: &str, value: impl Fn(&Sample) -> f64) -> Line {
+        let points: PlotPoints = self
+            .samples
+            .iter()
+            .map(|sample| [sample.step as f64, value(sample)])
+            .collect();
+        Line::new(points).name(name)
+    }
+
+    fn plot(&self, ui: &mut egui::Ui, id: &str, lines: Vec<Line>) {
+        ui.label(id);
+        Plot::new(id)
+            .height(80.0)
+            .legend(Legend::default())
+            .allow_drag(false)
+            .allow_zoom(false)
+            .allow_scroll(false)
+            .show(ui, |plot_ui| {
+                for line in lines {
+                    plot_ui.line(line);
+                }
+            });
+    }
+
+    pub fn ui(&self, ui: &mut egui::Ui) {
+        self.plot(
+            ui,
+            "Energy",
+            vec![
+                self.line("kinetic", |s| s.diagnostics.kinetic_energy as f64),
+                self.line("potential", |s| s.diagnostics.potential_energy as f64),
+                self.line("total", |s| s.diagnostics.total_energy() as f64),
+            ],
+        );
+        self.plot(
+            ui,
+            "Contacts",
+            vec![self.line("contacts", |s| s.diagnostics.contact_count as f64)],
+        );
+        self.plot(
+            ui,
+            "Max penetration",
+            vec![self.line("penetration", |s| s.diagnostics.max_penetration as f64)],
+        );
+        self.plot(
+            ui,
+            "Max joint drift",
+            vec![self.line("drift", |s| s.diagnostics.max_joint_drift as f64)],
+        );
+        self.plot(
+            ui,
+            "Step time (ms)",
+            vec![self.line("step time", |s| s.step_time_ms)],
+        );
+    }
+}
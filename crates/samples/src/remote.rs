@@ -0,0 +1,198 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+use sylt_2d::world::World;
+
+use crate::headless::BodyState;
+use crate::scene::Scene;
+
+/// A request made to the app from outside, taken on its next update.
+#[derive(Debug, Clone)]
+pub enum Request {
+    /// Loads the built-in demo at an index, with the seed of the app.
+    LoadDemo(usize),
+    LoadScene(Scene),
+    Step(u32),
+    SetPaused(bool),
+    SetGravity([f32; 2]),
+}
+
+/// Two bodies starting to touch during a step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Collision {
+    pub body_1: usize,
+    pub body_2: usize,
+    pub step: u64,
+}
+
+/// Connects the app to code driving it from outside, such as the JavaScript
+/// API: requests go in, the state of the bodies and collisions come out.
+#[derive(Default)]
+pub struct Remote {
+    pub requests: Vec<Request>,
+    /// The state of the bodies after the last update.
+    pub bodies: Vec<BodyState>,
+    listeners: Vec<Box<dyn FnMut(Collision)>>,
+    /// The pairs of bodies in contact after the last step.
+    touching: HashSet<(usize, usize)>,
+}
+
+pub type SharedRemote = Rc<RefCell<Remote>>;
+
+thread_local! {
+    static REMOTE: SharedRemote = SharedRemote::default();
+}
+
+/// The remote of the app running on the page.
+pub fn shared() -> SharedRemote {
+    REMOTE.with(Rc::clone)
+}
+
+impl Remote {
+    pub fn on_collision(&mut self, listener: impl FnMut(Collision) + 'static) {
+        self.listeners.push(Box::new(listener));
+    }
+
+    /// Replaces the pairs of bodies in contact, returning the collisions of
+    /// the pairs that were not touching before.
+    fn update_touching(&mut self, touching: HashSet<(usize, usize)>, step: u64) -> Vec<Collision> {
+        let mut collisions: Vec<_> = touching
+            .difference(&self.touching)
+            .map(|&(body_1, body_2)| Collision {
+                body_1,
+                body_2,
+                step,
+            })
+            .collect();
+        collisions.sort_by_key(|collision| (collision.body_1, collision.body_2));
+        self.touching = touching;
+        collisions
+    }
+}
+
+/// The pairs of bodies of `world` with at least one contact, by index.
+fn touching_pairs(world: &World) -> HashSet<(usize, usize)> {
+    let indices: HashMap<_, _> = world
+        .bodies
+        .iter()
+        .enumerate()
+        .map(|(i, body)| (Rc::as_ptr(body), i))
+        .collect();
+    world
+        .arbiters
+        .values()
+        .filter(|arbiter| arbiter.contacts.iter().flatten().next().is_some())
+        .filter_map(|arbiter| {
+            let index_1 = *indices.get(&Rc::as_ptr(&arbiter.body_1))?;
+            let index_2 = *indices.get(&Rc::as_ptr(&arbiter.body_2))?;
+            Some((index_1.min(index_2), index_1.max(index_2)))
+        })
+        .collect()
+}
+
+/// Takes the contacts of `world` as already known, for when its bodies are
+/// replaced or put back in a past state, which changes their indices.
+pub fn reset_contacts(remote: &SharedRemote, world: &World) {
+    remote.borrow_mut().touching = touching_pairs(world);
+}
+
+/// Tells the listeners of `remote` about the bodies that started touching
+/// during step number `step` of `world`.
+pub fn record_step(remote: &SharedRemote, world: &World, step: u64) {
+    let (mut listeners, collisions) = {
+        let mut remote = remote.borrow_mut();
+        // The contacts are followed even with no listener, so that one added
+        // later is not told about the contacts that already existed
+        let collisions = remote.update_touching(touching_pairs(world), step);
+        if remote.listeners.is_empty() || collisions.is_empty() {
+            return;
+        }
+        (std::mem::take(&mut remote.listeners), collisions)
+    };
+    // The listeners may make requests, so the remote is not borrowed while
+    // they run
+    for collision in collisions {
+        for listener in listeners.iter_mut() {
+            listener(collision);
+        }
+    }
+    let mut remote = remote.borrow_mut();
+    listeners.append(&mut remote.listeners);
+    remote.listeners = listeners;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use sylt_2d::body::Body;
+    use sylt_2d::math_utils::Vec2;
+
+    fn empty_world() -> World {
+        World::new(Vec2::new(0.0, -10.0), 10)
+    }
+
+    /// A box resting on the ground after one step.
+    fn touching_world() -> World {
+        let mut world = empty_world();
+        world.add_body(Body::new(Vec2::new(100.0, 20.0), f32::MAX));
+        let mut body = Body::new(Vec2::new(1.0, 1.0), 200.0);
+        body.position = Vec2::new(0.0, 10.4);
+        world.add_body(body);
+        assert!(world.step(1.0 / 60.0).is_ok());
+        world
+    }
+
+    fn listen(remote: &SharedRemote) -> Rc<RefCell<Vec<Collision>>> {
+        let collisions = Rc::new(RefCell::new(Vec::new()));
+        let sink = collisions.clone();
+        remote
+            .borrow_mut()
+            .on_collision(move |collision| sink.borrow_mut().push(collision));
+        collisions
+    }
+
+    #[test]
+    fn reports_contacts_once() {
+        let remote = SharedRemote::default();
+        let collisions = listen(&remote);
+        let world = touching_world();
+        record_step(&remote, &world, 1);
+        record_step(&remote, &world, 2);
+        let expected = Collision {
+            body_1: 0,
+            body_2: 1,
+            step: 1,
+        };
+        assert_eq!(*collisions.borrow(), vec![expected]);
+    }
+
+    #[test]
+    fn late_listener_is_not_told_about_existing_contacts() {
+        let remote = SharedRemote::default();
+        let world = touching_world();
+        record_step(&remote, &world, 1);
+        let collisions = listen(&remote);
+        record_step(&remote, &world, 2);
+        assert!(collisions.borrow().is_empty());
+    }
+
+    #[test]
+    fn reset_takes_the_contacts_of_the_new_world() {
+        let remote = SharedRemote::default();
+        let collisions = listen(&remote);
+        let world = touching_world();
+        record_step(&remote, &world, 1);
+
+        // A scene loaded with no contacts, whose bodies then touch
+        reset_contacts(&remote, &empty_world());
+        record_step(&remote, &world, 1);
+        assert_eq!(collisions.borrow().len(), 2);
+
+        // A jump back to a state with the same contacts
+        reset_contacts(&remote, &world);
+        record_step(&remote, &world, 2);
+        assert_eq!(collisions.borrow().len(), 2);
+    }
+}
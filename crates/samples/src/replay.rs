@@ -0,0 +1,142 @@
+use serde::{Deserialize, Serialize};
+
+use crate::inspector::BodyEdit;
+use crate::scene::{Scene, WorldFlags};
+
+/// A user input that changes the course of the simulation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum Action {
+    LoadDemo {
+        index: u32,
+        seed: u64,
+    },
+    LoadScene {
+        scene: Scene,
+        seed: u64,
+    },
+    Bomb,
+    SetFlags {
+        flags: WorldFlags,
+    },
+    SetTimeStep {
+        time_step: f32,
+    },
+    SetGravity {
+        gravity: [f32; 2],
+    },
+    SetIterations {
+        iterations: u32,
+    },
+    EditBody {
+        body: usize,
+        edit: BodyEdit,
+    },
+    Grab {
+        body: usize,
+        point: [f32; 2],
+    },
+    Drag {
+        target: [f32; 2],
+    },
+    Release,
+    /// Jumps to the history snapshot at `index`.
+    Restore {
+        index: usize,
+    },
+}
+
+/// An action taken after `step` steps of the current world.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Event {
+    pub step: u64,
+    #[serde(flatten)]
+    pub action: Action,
+}
+
+/// A session that can be replayed step for step. The first event always
+/// loads the scene the session started from.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Recording {
+    /// The solver flags when the session started, which built-in scenes take
+    /// from the running world.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub flags: Option<WorldFlags>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_step: Option<f32>,
+    pub events: Vec<Event>,
+}
+
+impl Recording {
+    pub fn from_json(source: &str) -> Result<Recording, serde_json::Error> {
+        serde_json::from_str(source)
+    }
+
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+#[derive(Default)]
+pub struct Recorder {
+    recording: Option<Recording>,
+}
+
+impl Recorder {
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    /// Starts recording a session run with `flags` and `time_step`.
+    pub fn start(&mut self, flags: WorldFlags, time_step: f32) {
+        self.recording = Some(Recording {
+            flags: Some(flags),
+            time_step: Some(time_step),
+            events: Vec::new(),
+        });
+    }
+
+    pub fn stop(&mut self) -> Option<Recording> {
+        self.recording.take()
+    }
+
+    pub fn record(&mut self, step: u64, action: &Action) {
+        if let Some(recording) = &mut self.recording {
+            recording.events.push(Event {
+                step,
+                action: action.clone(),
+            });
+        }
+    }
+}
+
+/// Feeds the events of a recording back at the steps they were taken.
+pub struct Player {
+    recording: Recording,
+    next: usize,
+}
+
+impl Player {
+    pub fn new(recording: Recording) -> Player {
+        Player { recording, next: 0 }
+    }
+
+    /// Returns the next action if it is due at `step`.
+    pub fn next_due(&mut self, step: u64) -> Option<Action> {
+        let event = self.recording.events.get(self.next)?;
+        if self.next > 0 && event.step > step {
+            return None;
+        }
+        self.next += 1;
+        Some(event.action.clone())
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.recording.events.len()
+    }
+
+    /// The number of events replayed so far and in total.
+    pub fn progress(&self) -> (usize, usize) {
+        (self.next, self.recording.events.len())
+    }
+}
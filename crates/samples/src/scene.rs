@@ -0,0 +1,323 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use sylt_2d::body::{Body, Shape};
+use sylt_2d::joint::Joint;
+use sylt_2d::math_utils::{Mat2x2, Vec2};
+use sylt_2d::world::World;
+
+use crate::spring::SpringConfig;
+
+include!(concat!(env!("OUT_DIR"), "/scenes.rs"));
+
+/// A scene description that can be loaded into a `World`.
+///
+/// Bodies are referenced by joints through their index in `bodies`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Scene {
+    pub name: String,
+    #[serde(default = "default_gravity")]
+    pub gravity: [f32; 2],
+    #[serde(default = "default_iterations")]
+    pub iterations: u32,
+    pub bodies: Vec<BodyDesc>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub joints: Vec<JointDesc>,
+    /// Solver flags, kept from the previous world when absent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub world_context: Option<WorldFlags>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct WorldFlags {
+    pub warm_starting: bool,
+    pub position_correction: bool,
+    pub accumulate_impulse: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "shape", rename_all = "snake_case")]
+pub enum ShapeDesc {
+    Box { width: [f32; 2] },
+    Polygon { vertices: Vec<[f32; 2]> },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BodyDesc {
+    #[serde(flatten)]
+    pub shape: ShapeDesc,
+    /// `None` creates a static body with infinite mass.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mass: Option<f32>,
+    pub position: [f32; 2],
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub rotation: f32,
+    /// `None` keeps the default friction of `Body`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub friction: Option<f32>,
+    #[serde(default, skip_serializing_if = "is_zero_vec")]
+    pub velocity: [f32; 2],
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub angular_velocity: f32,
+    /// Random offset in `[-jitter, jitter]` added to the position on load.
+    #[serde(default, skip_serializing_if = "is_zero_vec")]
+    pub position_jitter: [f32; 2],
+    /// Identifies the body across rebuilds of the world, to keep its colour.
+    /// Not saved.
+    #[serde(skip)]
+    pub key: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JointDesc {
+    pub bodies: [usize; 2],
+    pub anchor: [f32; 2],
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub softness: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bias_factor: Option<f32>,
+    /// Overrides `softness` and `bias_factor`, derived for the time step.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spring: Option<SpringConfig>,
+}
+
+fn default_gravity() -> [f32; 2] {
+    [0.0, -10.0]
+}
+
+fn default_iterations() -> u32 {
+    100
+}
+
+fn is_zero(value: &f32) -> bool {
+    *value == 0.0
+}
+
+fn is_zero_vec(value: &[f32; 2]) -> bool {
+    value[0] == 0.0 && value[1] == 0.0
+}
+
+pub(crate) fn vec2(value: [f32; 2]) -> Vec2 {
+    Vec2::new(value[0], value[1])
+}
+
+pub(crate) fn to_array(value: Vec2) -> [f32; 2] {
+    [value.x, value.y]
+}
+
+fn jitter(rng: &mut impl Rng, amount: f32) -> f32 {
+    if amount > 0.0 {
+        rng.gen_range(-amount..amount)
+    } else {
+        0.0
+    }
+}
+
+/// Parses all the scenes shipped in the `scenes` directory, ordered by file name.
+pub fn builtin_scenes() -> Vec<Scene> {
+    BUILTIN_SCENES
+        .iter()
+        .map(|(file_name, source)| {
+            Scene::from_json(source)
+                .unwrap_or_else(|e| panic!("Invalid built-in scene {}: {}", file_name, e))
+        })
+        .collect()
+}
+
+impl Scene {
+    pub fn from_json(source: &str) -> Result<Scene, serde_json::Error> {
+        serde_json::from_str(source)
+    }
+
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// A file name derived from the scene name, e.g. `a-teeter.json`.
+    pub fn file_name(&self) -> String {
+        let slug: String = self
+            .name
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() {
+                    c.to_ascii_lowercase()
+                } else {
+                    '-'
+                }
+            })
+            .collect();
+        format!("{}.json", slug)
+    }
+
+    /// Captures the current state of `world` as a scene.
+    ///
+    /// `springs` holds the spring configuration of the joints of the world.
+    pub fn from_world(
+        name: &str,
+        world: &World,
+        springs: &[Option<SpringConfig>],
+    ) -> Result<Scene, String> {
+        let bodies = world
+            .iter_bodies()
+            .map(|body| BodyDesc::from_body(&body))
+            .collect();
+
+        let index_of = |body: &Rc<RefCell<Body>>| {
+            world
+                .bodies
+                .iter()
+                .position(|other| Rc::ptr_eq(other, body))
+                .ok_or_else(|| "Joint references a body outside of the world".to_string())
+        };
+        let mut joints = Vec::with_capacity(world.joints.len());
+        for (i, joint) in world.joints.iter().enumerate() {
+            let body_1 = joint.body_1.borrow();
+            let rotation = Mat2x2::new_from_angle(body_1.rotation);
+            let anchor = body_1.position + rotation * joint.local_anchor_1;
+            joints.push(JointDesc {
+                bodies: [index_of(&joint.body_1)?, index_of(&joint.body_2)?],
+                anchor: to_array(anchor),
+                softness: Some(joint.softness),
+                bias_factor: Some(joint.bias_factor),
+                spring: springs.get(i).copied().flatten(),
+            });
+        }
+
+        Ok(Scene {
+            name: name.to_string(),
+            gravity: to_array(world.gravity),
+            iterations: world.iterations,
+            bodies,
+            joints,
+            world_context: Some(WorldFlags::from_world(world)),
+        })
+    }
+
+    /// The spring configuration of each joint of the scene.
+    pub fn springs(&self) -> Vec<Option<SpringConfig>> {
+        self.joints.iter().map(|joint| joint.spring).collect()
+    }
+
+    /// Replaces `world` with a new one built from this scene, with the spring
+    /// joints tuned for `time_step`. The position jitter is drawn from `rng`.
+    pub fn load_into(
+        &self,
+        world: &mut World,
+        time_step: f32,
+        rng: &mut impl Rng,
+    ) -> Result<(), String> {
+        let mut new_world = World::new(vec2(self.gravity), self.iterations);
+        self.world_context
+            .unwrap_or_else(|| WorldFlags::from_world(world))
+            .apply(&mut new_world);
+
+        let bodies: Vec<Body> = self
+            .bodies
+            .iter()
+            .map(|desc| {
+                let mut body = desc.build();
+                body.position.x += jitter(rng, desc.position_jitter[0]);
+                body.position.y += jitter(rng, desc.position_jitter[1]);
+                body
+            })
+            .collect();
+        for body in bodies.iter() {
+            new_world.add_body(body.clone());
+        }
+
+        for (i, desc) in self.joints.iter().enumerate() {
+            let [index_1, index_2] = desc.bodies;
+            let (Some(body_1), Some(body_2)) = (bodies.get(index_1), bodies.get(index_2)) else {
+                return Err(format!(
+                    "Joint {} in scene {:?} references a missing body",
+                    i, self.name
+                ));
+            };
+            let mut joint = Joint::new(
+                body_1.clone(),
+                body_2.clone(),
+                vec2(desc.anchor),
+                &new_world,
+            );
+            if let Some(softness) = desc.softness {
+                joint.softness = softness;
+            }
+            if let Some(bias_factor) = desc.bias_factor {
+                joint.bias_factor = bias_factor;
+            }
+            if let Some(spring) = desc.spring {
+                spring.apply(&mut joint, time_step);
+            }
+            new_world.add_joint(joint);
+        }
+
+        *world = new_world;
+        Ok(())
+    }
+}
+
+impl WorldFlags {
+    pub fn from_world(world: &World) -> WorldFlags {
+        WorldFlags {
+            warm_starting: world.world_context.warm_starting,
+            position_correction: world.world_context.position_correction,
+            accumulate_impulse: world.world_context.accumulate_impulse,
+        }
+    }
+
+    pub fn apply(&self, world: &mut World) {
+        world.world_context.warm_starting = self.warm_starting;
+        world.world_context.position_correction = self.position_correction;
+        world.world_context.accumulate_impulse = self.accumulate_impulse;
+    }
+}
+
+impl BodyDesc {
+    pub fn from_body(body: &Body) -> BodyDesc {
+        let shape = match body.shape {
+            Shape::Box => ShapeDesc::Box {
+                width: to_array(body.width),
+            },
+            Shape::ConvexPolygon => ShapeDesc::Polygon {
+                vertices: body
+                    .get_polygon()
+                    .get_vertices()
+                    .into_iter()
+                    .map(to_array)
+                    .collect(),
+            },
+        };
+        BodyDesc {
+            shape,
+            mass: (body.mass != f32::MAX).then_some(body.mass),
+            position: to_array(body.position),
+            rotation: body.rotation,
+            friction: Some(body.friction),
+            velocity: to_array(body.velocity),
+            angular_velocity: body.angular_velocity,
+            position_jitter: [0.0, 0.0],
+            key: None,
+        }
+    }
+
+    /// Builds the body at its nominal position, without any jitter.
+    pub fn build(&self) -> Body {
+        let mass = self.mass.unwrap_or(f32::MAX);
+        let mut body = match &self.shape {
+            ShapeDesc::Box { width } => Body::new(vec2(*width), mass),
+            ShapeDesc::Polygon { vertices } => {
+                Body::new_polygon(vertices.iter().copied().map(vec2).collect(), mass)
+            }
+        };
+        body.position = vec2(self.position);
+        body.rotation = self.rotation;
+        if let Some(friction) = self.friction {
+            body.friction = friction;
+        }
+        body.velocity = vec2(self.velocity);
+        body.angular_velocity = self.angular_velocity;
+        body
+    }
+}
@@ -1,471 +1,587 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use common::camera::Camera;
+use common::draw::draw_body;
+use common::link::Link;
+use common::window::{new_window, EguiModel};
+use nannou::color::IntoLinSrgba;
 use nannou::prelude::*;
-use nannou::wgpu::{DeviceDescriptor, Limits};
 use nannou_egui::{self, egui, Egui};
-use sylt_2d::body::{Body, ConvexPolygon, Shape};
-use sylt_2d::joint::Joint;
+use rand::{Rng, SeedableRng};
+use rand_pcg::Pcg32;
+use sylt_2d::body::Body;
 use sylt_2d::math_utils::{Mat2x2, Vec2};
 use sylt_2d::world::World;
 
+use crate::colors::{body_colors, BodyKeys, ColorMode};
+use crate::diagnostics::Diagnostics;
+use crate::editor::{Editor, Handle};
+use crate::export::save_text_file;
+use crate::headless::{find_builtin_scene_index, BodyState};
+use crate::history::History;
+use crate::inspector::body_inspector_ui;
+use crate::layers::{aabb, draw_arbiter_layers, draw_body_layers, DebugLayers};
+use crate::picking::{body_under, is_static, pick_body, MouseJoint};
+use crate::plots::{now_ms, Plots};
+use crate::remote::{self, Request, SharedRemote};
+use crate::replay::{Action, Player, Recorder, Recording};
+use crate::scene::{builtin_scenes, to_array, vec2, Scene, WorldFlags};
+use crate::spring::{retune_joints, SpringConfig};
+use crate::timestep::{FixedTimestep, Pose};
+
 const ITERATIONS: u32 = 100;
 
 pub struct EguiSettings {
-    scale: f32,
-    x_translate: f32,
-    y_translate: f32,
     color: Srgb<u8>,
 }
 
 pub struct Model {
     _window: window::Id,
     time_step: f32,
+    timestep: FixedTimestep,
+    paused: bool,
+    time_scale: f32,
+    /// The number of steps taken by the "Step N" button.
+    step_count: u32,
+    /// Steps requested by the user, taken on the next update.
+    pending_steps: u32,
+    /// The number of steps taken since the world was built.
+    step_index: u64,
+    history: History,
+    /// Measured on the world after the last step.
+    diagnostics: Diagnostics,
+    plots: Plots,
+    show_plots: bool,
+    /// Actions requested from the UI, performed on the next update.
+    actions: Vec<Action>,
+    /// Seeds `rng` whenever a scene is loaded.
+    seed: u64,
+    /// The source of all the randomness of the scenes.
+    rng: Pcg32,
+    recorder: Recorder,
+    last_recording: Option<Recording>,
+    player: Option<Player>,
+    /// A recording to replay from the next update.
+    pending_replay: Option<Recording>,
+    /// A recording pasted in the UI.
+    replay_source: String,
     demo_index: u32,
+    scenes: Vec<Scene>,
     world: World,
-    bomb: bool,
+    /// The spring configuration of each joint of `world`.
+    joint_springs: Vec<Option<SpringConfig>>,
     egui: Egui,
     settings: EguiSettings,
+    camera: Camera,
+    /// Keeps the inspected body at the centre of the view.
+    follow: bool,
+    /// The last mouse position while dragging the view.
+    pan_from: Option<Point2>,
+    fit_view_flag: bool,
     is_first_frame: bool,
-    load_demo_flag: bool,
+    export_scene_flag: bool,
+    mouse_joint: Option<MouseJoint>,
+    /// The body shown in the inspector.
+    inspected: Option<usize>,
+    layers: DebugLayers,
+    color_mode: ColorMode,
+    body_keys: BodyKeys,
+    editor: Option<Editor>,
+    toggle_edit_flag: bool,
+    /// Requests from the JavaScript API, and the state shared back.
+    remote: SharedRemote,
+}
+impl EguiModel for Model {
+    fn egui(&mut self) -> &mut Egui {
+        &mut self.egui
+    }
 }
+
 pub async fn create_model(app: &App) -> Model {
-    let _window = app
-        .new_window()
-        .device_descriptor(DeviceDescriptor {
-            limits: Limits {
-                max_texture_dimension_2d: 8192,
-                ..Limits::downlevel_webgl2_defaults()
-            },
-            ..Default::default()
-        })
-        .view(view)
-        .raw_event(raw_window_event)
+    let window = new_window::<Model>(app)
+        .view(|app, model: &Model, frame| view(app, model, &frame))
         .key_pressed(key_pressed)
+        .mouse_pressed(mouse_pressed)
+        .mouse_moved(mouse_moved)
+        .mouse_released(mouse_released)
+        .mouse_wheel(mouse_wheel)
         .build_async()
         .await
         .unwrap();
+    new_model(app, window)
+}
+
+/// Builds the model drawing in an existing window, whose events are passed on
+/// to the handlers of this module.
+pub fn new_model(app: &App, _window: window::Id) -> Model {
     let window = app.window(_window).unwrap();
     let egui = Egui::from_window(&window);
     let world = World::new(Vec2::new(0.0, -10.0), ITERATIONS);
     Model {
         _window,
         world,
+        joint_springs: Vec::new(),
         demo_index: 0,
-        bomb: false,
+        scenes: builtin_scenes(),
         time_step: 1.0 / 60.0,
+        timestep: FixedTimestep::default(),
+        paused: false,
+        time_scale: 1.0,
+        step_count: 10,
+        pending_steps: 0,
+        step_index: 0,
+        history: History::default(),
+        diagnostics: Diagnostics::default(),
+        plots: Plots::default(),
+        show_plots: false,
+        actions: Vec::new(),
+        seed: 0,
+        rng: Pcg32::seed_from_u64(0),
+        recorder: Recorder::default(),
+        last_recording: None,
+        player: None,
+        pending_replay: None,
+        replay_source: String::new(),
         egui,
-        settings: EguiSettings {
-            scale: 18.0,
-            x_translate: 0.0,
-            y_translate: 0.0,
-            color: WHITE,
-        },
+        settings: EguiSettings { color: WHITE },
+        camera: Camera::default(),
+        follow: false,
+        pan_from: None,
+        fit_view_flag: false,
         is_first_frame: true,
-        load_demo_flag: false,
+        export_scene_flag: false,
+        mouse_joint: None,
+        inspected: None,
+        layers: DebugLayers::default(),
+        color_mode: ColorMode::StaticDynamic,
+        body_keys: BodyKeys::default(),
+        editor: None,
+        toggle_edit_flag: false,
+        remote: remote::shared(),
+    }
+}
+
+/// Connects the model to `remote` rather than to the remote of the page, to
+/// drive one of several models separately.
+pub fn set_remote(model: &mut Model, remote: SharedRemote) {
+    model.remote = remote;
+}
+
+/// Applies the settings of a deep link, before the first update.
+pub fn apply_link(model: &mut Model, link: &Link) {
+    if let Some(demo) = &link.demo {
+        match find_builtin_scene_index(demo) {
+            Some(index) => model.demo_index = index as u32,
+            None => eprintln!("Error: no demo named {}", demo),
+        }
+    }
+    if let Some(seed) = link.seed {
+        model.seed = seed;
+    }
+    if let Some(paused) = link.paused {
+        model.paused = paused;
+    }
+    if let Some(scale) = link.scale {
+        model.camera.scale = scale;
+    }
+    if let Some(translate) = link.translate {
+        model.camera.center = vec2(translate);
+    }
+    // Set once the demo is loaded, which would reset it
+    if let Some(gravity) = link.gravity {
+        model.actions.push(Action::SetGravity { gravity });
+    }
+}
+
+/// The deep link to the current demo and settings.
+pub fn link(model: &Model) -> Link {
+    Link {
+        mode: None,
+        demo: Some(scene_name(model).to_string()),
+        scale: Some(model.camera.scale),
+        translate: Some(to_array(model.camera.center)),
+        gravity: Some(to_array(model.world.gravity)),
+        seed: Some(model.seed),
+        paused: Some(model.paused),
     }
 }
 
 fn launch_bomb(model: &mut Model) {
     let mut bomb = Body::new(Vec2::new(1.0, 1.0), 50.0);
     bomb.friction = 0.2;
-    bomb.position = Vec2::new(random_range(-15.0, 15.0), 15.0);
-    bomb.rotation = random_range(-1.5, 1.5);
+    bomb.position = Vec2::new(model.rng.gen_range(-15.0..15.0), 15.0);
+    bomb.rotation = model.rng.gen_range(-1.5..1.5);
     bomb.velocity = bomb.position * -1.5;
-    bomb.angular_velocity = random_range(-20.0, 20.0);
+    bomb.angular_velocity = model.rng.gen_range(-20.0..20.0);
     model.world.add_body(bomb.clone());
 }
 
-fn demo1(_model: &mut Model) {
-    // Single Shapes Falling
-    let mut body1 = Body::new(Vec2::new(100.0, 20.0), f32::MAX);
-    body1.position = Vec2::new(0.0, -0.5 * body1.width.y);
-    _model.world.add_body(body1.clone());
-
-    let mut body2 = Body::new(Vec2::new(1.0, 1.0), 200.0);
-    body2.position = Vec2::new(0.0, 3.0);
-    _model.world.add_body(body2.clone());
-
-    // polygon: A hexagon
-    let hexagon: Vec<Vec2> = vec![
-        Vec2 { x: 0.0, y: 1.0 },    // Top vertex
-        Vec2 { x: -0.87, y: 0.5 },  // Top-left vertex
-        Vec2 { x: -0.87, y: -0.5 }, // Bottom-left vertex
-        Vec2 { x: 0.0, y: -1.0 },   // Bottom vertex
-        Vec2 { x: 0.87, y: -0.5 },  // Bottom-right vertex
-        Vec2 { x: 0.87, y: 0.5 },   // Top-right vertex
-    ];
-    // polygon: A pentagon
-    let pentagon: Vec<Vec2> = vec![
-        Vec2 { x: 0.0, y: 1.0 },     // Top vertex
-        Vec2 { x: -0.95, y: 0.31 },  // Top-left vertex
-        Vec2 { x: -0.59, y: -0.81 }, // Bottom-left vertex
-        Vec2 { x: 0.59, y: -0.81 },  // Bottom-right vertex
-        Vec2 { x: 0.95, y: 0.31 },   // Top-right vertex
-    ];
-    let mut pentagon_body = Body::new_polygon(pentagon, 2.0);
-    let mut hexagon_body = Body::new_polygon(hexagon, 2.0);
-    pentagon_body.position = Vec2::new(0.0, 5.0);
-    pentagon_body.friction = 100.0;
-    hexagon_body.position = Vec2::new(5.0, 4.0);
-    hexagon_body.rotation = 45.0;
-    _model.world.add_body(pentagon_body.clone());
-    _model.world.add_body(hexagon_body.clone());
-}
-
-fn demo2(model: &mut Model) {
-    // Simple Pendulum
-    let mut body1 = Body::new(Vec2::new(100.0, 20.0), f32::MAX);
-    body1.friction = 0.2;
-    body1.position = Vec2::new(0.0, -0.5 * body1.width.y);
-    body1.rotation = 0.0;
-    model.world.add_body(body1.clone());
-
-    let mut body2 = Body::new(Vec2::new(1.0, 1.0), 100.0);
-    body2.friction = 0.2;
-    body2.position = Vec2::new(9.0, 11.0);
-    body2.rotation = 0.0;
-    model.world.add_body(body2.clone());
-
-    let joint = Joint::new(body1, body2, Vec2::new(0.0, 11.0), &model.world);
-    model.world.add_joint(joint);
-}
-
-fn demo3(model: &mut Model) {
-    let friction_values = [0.75, 0.5, 0.35, 0.1, 0.0];
-
-    let mut body = Body::new(Vec2::new(100.0, 20.0), f32::MAX);
-    body.position = Vec2::new(0.0, -0.5 * body.width.y);
-    model.world.add_body(body.clone());
-
-    let mut body2 = Body::new(Vec2::new(13.0, 0.25), f32::MAX);
-    body2.position = Vec2::new(-2.0, 11.0);
-    body2.rotation = -0.25;
-    model.world.add_body(body2.clone());
-
-    // Additional bodies with varying frictions
-    for (i, &friction) in friction_values.iter().enumerate() {
-        let mut body = Body::new(Vec2::new(0.5, 0.5), 25.0);
-        body.friction = friction;
-        body.position = Vec2::new(-7.5 + 2.0 * i as f32, 14.0);
-        model.world.add_body(body.clone());
-    }
-}
-
-fn demo4(model: &mut Model) {
-    // Vertical Stack
-    let mut ground = Body::new(Vec2::new(100.0, 20.0), f32::MAX);
-    ground.friction = 0.2;
-    ground.position = Vec2::new(0.0, -0.5 * ground.width.y);
-    model.world.add_body(ground.clone());
-
-    for i in 0..10 {
-        let mut body = Body::new(Vec2::new(1.0, 1.0), 1.0);
-        body.friction = 0.2;
-        body.position = Vec2::new(random::<f32>() * 0.2 - 0.1, 0.51 + 1.05 * i as f32);
-        model.world.add_body(body.clone());
-    }
-}
-
-fn demo5(model: &mut Model) {
-    // Pyramid
-    let mut ground = Body::new(Vec2::new(100.0, 20.0), f32::MAX);
-    ground.friction = 0.2;
-    ground.position = Vec2::new(0.0, -0.5 * ground.width.y);
-    model.world.add_body(ground.clone());
-
-    let mut x = Vec2::new(-6.0, 0.75);
-    for i in 0..12 {
-        let mut y = x;
-        for _j in i..12 {
-            let mut body = Body::new(Vec2::new(1.0, 1.0), 10.0);
-            body.friction = 0.2;
-            body.position = y;
-            model.world.add_body(body.clone());
-
-            y.x += 1.125;
-        }
-        x.x += 0.5625;
-        x.y += 2.0;
-    }
-}
-
-fn demo6(model: &mut Model) {
-    // A Teeter
-    let mut body1 = Body::new(Vec2::new(100.0, 20.0), f32::MAX);
-    body1.position = Vec2::new(0.0, -0.5 * body1.width.y);
-    model.world.add_body(body1.clone());
-
-    let mut body2 = Body::new(Vec2::new(12.0, 0.25), 10.0);
-    body2.position = Vec2::new(0.0, 3.0);
-    model.world.add_body(body2.clone());
-
-    let mut body3 = Body::new(Vec2::new(0.5, 0.5), 2.0);
-    body3.position = Vec2::new(-5.0, 5.0);
-    model.world.add_body(body3.clone());
-
-    let mut body4 = Body::new(Vec2::new(0.5, 0.5), 2.0);
-    body4.position = Vec2::new(-5.5, 5.0);
-    model.world.add_body(body4.clone());
-
-    let mut body5 = Body::new(Vec2::new(1.0, 1.0), 55.0);
-    body5.position = Vec2::new(5.5, 15.0);
-    model.world.add_body(body5.clone());
-
-    let joint = Joint::new(body1, body2, Vec2::new(0.0, 3.0), &model.world);
-    model.world.add_joint(joint);
-}
-
-fn demo7(model: &mut Model) {
-    let mut ground = Body::new(Vec2::new(100.0, 20.0), f32::MAX);
-    ground.friction = 0.2;
-    ground.position = Vec2::new(0.0, -0.5 * ground.width.y);
-    model.world.add_body(ground.clone());
-
-    let num_planks = 15;
-    let mass = 10.0;
-    let frequency_hz = 2.0;
-    let damping_ratio = 0.7;
-    let omega = 2.0 * std::f32::consts::PI * frequency_hz;
-    let d = 2.0 * mass * damping_ratio * omega;
-    let k = mass * omega * omega;
-    let time_step = 1.0 / 60.0;
-    let softness = 1.0 / (d + time_step * k);
-    let bias_factor = time_step * k / (d + time_step * k);
-
-    for i in 0..=num_planks {
-        let mut plank = Body::new(Vec2::new(1.0, 0.25), mass);
-        plank.friction = 0.2;
-        plank.position = Vec2::new(-8.5 + 1.25 * i as f32, 5.0);
-        model.world.add_body(plank.clone());
-
-        let mut joint = Joint::new(
-            plank.clone(),
-            ground.clone(),
-            Vec2::new(-9.125 + 1.25 * i as f32, 5.0),
-            &model.world,
-        );
-        joint.softness = softness;
-        joint.bias_factor = bias_factor;
-        model.world.add_joint(joint);
+/// Performs an action on the simulation, recording it when a session is
+/// being recorded.
+fn perform(model: &mut Model, action: Action) {
+    model.recorder.record(model.step_index, &action);
+    match action {
+        Action::LoadDemo { index, seed } => {
+            model.demo_index = index;
+            model.seed = seed;
+            load_demo(model);
+        }
+        Action::LoadScene { scene, seed } => {
+            model.seed = seed;
+            load_scene(model, &scene);
+        }
+        Action::Bomb => launch_bomb(model),
+        Action::SetFlags { flags } => flags.apply(&mut model.world),
+        Action::SetTimeStep { time_step } => {
+            model.time_step = time_step;
+            retune_joints(&mut model.world, &model.joint_springs, time_step);
+        }
+        Action::SetGravity { gravity } => {
+            model.world.gravity = vec2(gravity);
+            if let Some(editor) = &mut model.editor {
+                editor.scene.gravity = gravity;
+            }
+        }
+        Action::SetIterations { iterations } => {
+            model.world.iterations = iterations;
+            if let Some(editor) = &mut model.editor {
+                editor.scene.iterations = iterations;
+            }
+        }
+        Action::EditBody { body, edit } => {
+            if let Some(body) = model.world.bodies.get(body) {
+                edit.apply(&mut body.borrow_mut());
+            }
+        }
+        Action::Grab { body, point } => {
+            if body < model.world.bodies.len() {
+                model.mouse_joint = Some(MouseJoint::new(&model.world, body, vec2(point)));
+            }
+        }
+        Action::Drag { target } => {
+            if let Some(mouse_joint) = &mut model.mouse_joint {
+                mouse_joint.target = vec2(target);
+            }
+        }
+        Action::Release => {
+            if let Some(mouse_joint) = model.mouse_joint.take() {
+                mouse_joint.release(&model.world);
+            }
+        }
+        Action::Restore { index } => jump_to(model, index),
     }
 }
 
-// Dominos demo
-fn demo8(model: &mut Model) {
-    let mut b1 = Body::new(Vec2::new(100.0, 20.0), f32::MAX);
-    b1.position = Vec2::new(0.0, -0.5 * b1.width.y);
-    model.world.add_body(b1.clone());
+/// Performs an action coming from the user, unless a recording is replayed.
+fn user_action(model: &mut Model, action: Action) {
+    if model.player.is_none() {
+        perform(model, action);
+    }
+}
 
-    let mut b = Body::new(Vec2::new(12.0, 0.5), f32::MAX);
-    b.position = Vec2::new(-1.5, 10.0);
-    model.world.add_body(b.clone());
-
-    for i in 0..10 {
-        let mut domino = Body::new(Vec2::new(0.2, 2.0), 10.0);
-        domino.position = Vec2::new(-6.0 + 1.0 * i as f32, 11.125);
-        domino.friction = 0.1;
-        model.world.add_body(domino.clone());
-    }
-
-    let mut bb = Body::new(Vec2::new(14.0, 0.5), f32::MAX);
-    bb.position = Vec2::new(1.0, 6.0);
-    bb.rotation = 0.3;
-    model.world.add_body(bb.clone());
-
-    let mut b2 = Body::new(Vec2::new(0.5, 3.0), f32::MAX);
-    b2.position = Vec2::new(-7.0, 4.0);
-    model.world.add_body(b2.clone());
-
-    let mut b3 = Body::new(Vec2::new(12.0, 0.25), 10.0);
-    b3.position = Vec2::new(-0.9, 1.0);
-    model.world.add_body(b3.clone());
-
-    let joint1 = Joint::new(b1.clone(), b3, Vec2::new(-2.0, 3.0), &model.world);
-    model.world.add_joint(joint1);
-
-    let mut b4 = Body::new(Vec2::new(0.5, 0.5), 16.0);
-    b4.position = Vec2::new(-10.0, 15.0);
-    b4.rotation = 0.0;
-    b4.friction = 0.2;
-    model.world.add_body(b4.clone());
-
-    let joint2 = Joint::new(b2, b4, Vec2::new(-7.0, 15.0), &model.world);
-    model.world.add_joint(joint2);
-
-    let mut b5 = Body::new(Vec2::new(2.0, 2.0), 10.0);
-    b5.position = Vec2::new(6.0, 2.5);
-    b5.friction = 0.1;
-    model.world.add_body(b5.clone());
-
-    let joint3 = Joint::new(b1, b5.clone(), Vec2::new(6.0, 2.6), &model.world);
-    model.world.add_joint(joint3);
-
-    let mut b6 = Body::new(Vec2::new(2.0, 0.2), 10.0);
-    b6.position = Vec2::new(6.0, 3.6);
-    model.world.add_body(b6.clone());
-
-    let joint4 = Joint::new(b5, b6, Vec2::new(7.0, 3.5), &model.world);
-    model.world.add_joint(joint4);
-}
-
-// Multi-pendulum demo
-fn demo9(model: &mut Model) {
-    let mut ground = Body::new(Vec2::new(100.0, 20.0), f32::MAX);
-    ground.friction = 0.2;
-    ground.position = Vec2::new(0.0, -0.5 * ground.width.y);
-    ground.rotation = 0.0;
-    model.world.add_body(ground.clone());
-
-    let mut b1 = ground;
-    let mass = 10.0;
-    let frequency_hz = 4.0;
-    let damping_ratio = 0.7;
-
-    let omega = 2.0 * std::f32::consts::PI * frequency_hz;
-    let d = 2.0 * mass * damping_ratio * omega;
-    let k = mass * omega * omega;
-
-    let time_step = model.time_step;
-    let softness = 1.0 / (d + time_step * k);
-    let bias_factor = time_step * k / (d + time_step * k);
-
-    let y = 12.0;
-
-    for i in 0..15 {
-        let mut pendulum = Body::new(Vec2::new(0.75, 0.25), mass);
-        pendulum.friction = 0.2;
-        pendulum.position = Vec2::new(0.5 + i as f32, y);
-        pendulum.rotation = 0.0;
-        model.world.add_body(pendulum.clone());
-
-        let mut joint = Joint::new(b1, pendulum.clone(), Vec2::new(i as f32, y), &model.world);
-        joint.softness = softness;
-        joint.bias_factor = bias_factor;
-        model.world.add_joint(joint);
-
-        b1 = pendulum;
-    }
-}
-
-// A Pawn and the pendulum
-fn demo10(_model: &mut Model) {
-    // A Pawn and the pendulum
-    let pentagon: Vec<Vec2> = vec![
-        Vec2 { x: 0.0, y: 1.0 },     // Top vertex
-        Vec2 { x: -0.95, y: 0.31 },  // Top-left vertex
-        Vec2 { x: -0.59, y: -0.81 }, // Bottom-left vertex
-        Vec2 { x: 0.59, y: -0.81 },  // Bottom-right vertex
-        Vec2 { x: 0.95, y: 0.31 },   // Top-right vertex
-    ];
-    let mut pawn_head = ConvexPolygon::new(vec![
-        Vec2 { x: 0.0, y: 0.4 },   // Top center (head)
-        Vec2 { x: -0.4, y: 0.0 },  // Upper left
-        Vec2 { x: -0.2, y: -0.4 }, // Lower left (neck)
-        Vec2 { x: 0.2, y: -0.4 },  // Lower right (neck)
-        Vec2 { x: 0.4, y: 0.0 },   // Upper right
-    ]);
-    pawn_head.scale(2.0);
-
-    // Define the bottom part (body and base) as a convex polygon
-    let mut pawn_trunk = ConvexPolygon::new(vec![
-        Vec2 { x: -0.8, y: 0.8 },  // Base left
-        Vec2 { x: -0.6, y: 0.0 },  // Mid-body left
-        Vec2 { x: -0.3, y: -0.8 }, // Bottom left
-        Vec2 { x: 0.3, y: -0.8 },  // Bottom right
-        Vec2 { x: 0.6, y: 0.0 },   // Mid-body right
-        Vec2 { x: 0.8, y: 0.8 },   // Base right
-    ]);
-    pawn_trunk.scale(2.0);
-    let mut body1 = Body::new(Vec2::new(1000.0, 20.0), f32::MAX);
-    body1.position = Vec2::new(0.0, -0.5 * body1.width.y);
-    _model.world.add_body(body1.clone());
-
-    let mut pentagon_body = Body::new_polygon(pentagon, 55.0);
-    let mut pawn_head = Body::new_polygon(pawn_head.get_vertices(), 10.0);
-    let mut pawn_body = Body::new_polygon(pawn_trunk.get_vertices(), 10.0);
-    pentagon_body.friction = 0.2;
-    pentagon_body.position = Vec2::new(-9.0, 8.0);
-    pentagon_body.rotation = 0.0;
-
-    pawn_body.position = Vec2::new(5.0, 4.0);
-    pawn_head.position = Vec2::new(
-        pawn_body.position.x,
-        pawn_body.position.y + 0.5 * pawn_body.width.y + 0.5 * pawn_head.width.y,
-    );
-    _model.world.add_body(pentagon_body.clone());
-    _model.world.add_body(pawn_head.clone());
-    _model.world.add_body(pawn_body.clone());
-    let joint3 = Joint::new(pawn_head, pawn_body, Vec2::new(5.0, 3.0), &_model.world);
-    _model.world.add_joint(joint3);
+/// Replays `recording` with the solver flags and time step it was recorded
+/// with.
+fn start_replay(model: &mut Model, recording: Recording) {
+    if let Some(flags) = recording.flags {
+        perform(model, Action::SetFlags { flags });
+    }
+    if let Some(time_step) = recording.time_step {
+        perform(model, Action::SetTimeStep { time_step });
+    }
+    model.player = Some(Player::new(recording));
+}
+
+/// Performs the replayed actions due at the current step.
+fn replay(model: &mut Model) {
+    while let Some(action) = model
+        .player
+        .as_mut()
+        .and_then(|player| player.next_due(model.step_index))
+    {
+        // Keep playing through the jumps back in time of the recording
+        let restore = matches!(action, Action::Restore { .. });
+        perform(model, action);
+        if restore {
+            model.paused = false;
+        }
+    }
+    if model.player.as_ref().is_some_and(Player::is_finished) {
+        model.player = None;
+    }
+}
+
+fn step_world(model: &mut Model) {
+    replay(model);
+    model.timestep.record(&model.world);
+    if let Some(mouse_joint) = &model.mouse_joint {
+        mouse_joint.apply(&model.world);
+    }
+    let start = now_ms();
+    let step = model.world.step(model.time_step);
+    let step_time_ms = now_ms() - start;
+    if let Err(e) = step {
+        eprintln!("Error: {}", e);
+    }
+    model.step_index += 1;
+    model
+        .history
+        .record(&model.world, &model.rng, model.step_index);
+    model.diagnostics = Diagnostics::of_world(&model.world);
+    model
+        .plots
+        .record(model.step_index, model.diagnostics, step_time_ms);
+    remote::record_step(&model.remote, &model.world, model.step_index);
+}
 
-    let joint = Joint::new(body1, pentagon_body, Vec2::new(0.0, 11.0), &_model.world);
-    _model.world.add_joint(joint);
+/// Turns the requests made through the remote into actions.
+fn take_requests(model: &mut Model) {
+    let requests = std::mem::take(&mut model.remote.borrow_mut().requests);
+    for request in requests {
+        match request {
+            Request::LoadDemo(index) => {
+                model.demo_index = index as u32;
+                model.actions.push(Action::LoadDemo {
+                    index: model.demo_index,
+                    seed: model.seed,
+                });
+            }
+            Request::LoadScene(scene) => model.actions.push(Action::LoadScene {
+                scene,
+                seed: model.seed,
+            }),
+            Request::Step(steps) => model.pending_steps += steps,
+            Request::SetPaused(paused) => model.paused = paused,
+            Request::SetGravity(gravity) => model.actions.push(Action::SetGravity { gravity }),
+        }
+    }
 }
 
-pub fn update(_app: &App, _model: &mut Model, _update: Update) {
-    if _model.is_first_frame {
-        let step = _model.world.step(_model.time_step);
+/// Starts recording the history of a newly built world.
+fn reset_history(model: &mut Model) {
+    model.step_index = 0;
+    model.history.clear();
+    model.history.record(&model.world, &model.rng, 0);
+    model.diagnostics = Diagnostics::of_world(&model.world);
+    model.plots.clear();
+}
+
+/// Restores the world to the history snapshot at `index` and pauses there.
+fn jump_to(model: &mut Model, index: usize) {
+    if let Some(step) = model
+        .history
+        .restore(index, &mut model.world, &mut model.rng)
+    {
+        model.step_index = step;
+        model.paused = true;
+        model.timestep.reset();
+        model.diagnostics = Diagnostics::of_world(&model.world);
+        remote::reset_contacts(&model.remote, &model.world);
+    }
+}
+
+pub fn update(app: &App, model: &mut Model, update: Update) {
+    update_hosted(app, model, update, |_| {});
+}
+
+/// Updates the model like `update`, with `host_ui` adding the interface of the
+/// app hosting the example to the egui frame of the example.
+pub fn update_hosted(
+    app: &App,
+    model: &mut Model,
+    update: Update,
+    host_ui: impl FnOnce(&egui::Context),
+) {
+    if let Some(window) = app.window(model._window) {
+        model.camera.set_window(&window);
+    }
+    if model.is_first_frame {
+        let step = model.world.step(model.time_step);
         if let Err(e) = step {
             eprintln!("Error: {}", e);
         }
         // Load the initial demo
-        load_demo(_model);
-        _model.is_first_frame = false;
+        load_demo(model);
+        model.is_first_frame = false;
     }
-    let step = _model.world.step(_model.time_step);
-    if let Err(e) = step {
-        eprintln!("Error: {}", e);
+    take_requests(model);
+    for action in std::mem::take(&mut model.actions) {
+        user_action(model, action);
+    }
+    if let Some(recording) = model.pending_replay.take() {
+        start_replay(model, recording);
+    }
+    replay(model);
+
+    // The simulation is paused while editing
+    if model.editor.is_none() {
+        if !model.paused {
+            let elapsed = update.since_last.as_secs_f32() * model.time_scale;
+            for _ in 0..model.timestep.steps(elapsed, model.time_step) {
+                step_world(model);
+            }
+        } else {
+            // Show the state the simulation stopped at, with no time left over
+            // to interpolate with when it resumes
+            model.timestep.reset();
+        }
+        if model.pending_steps > 0 {
+            for _ in 0..model.pending_steps {
+                step_world(model);
+            }
+            // Show the exact state reached rather than an interpolated one
+            model.timestep.reset();
+        }
+    }
+    model.pending_steps = 0;
+    model.remote.borrow_mut().bodies = BodyState::of_world(&model.world);
+    model.body_keys.update(&model.world);
+
+    if model.toggle_edit_flag && model.player.is_none() {
+        toggle_edit_mode(model);
     }
+    model.toggle_edit_flag = false;
 
-    if _model.load_demo_flag {
-        load_demo(_model);
-        _model.load_demo_flag = false;
+    if model.export_scene_flag {
+        export_scene(model);
+        model.export_scene_flag = false;
     }
 
-    if _model.bomb {
-        launch_bomb(_model);
-        _model.bomb = false;
+    if model.fit_view_flag {
+        fit_view(model);
+        model.fit_view_flag = false;
     }
 
-    let egui = &mut _model.egui;
-    let settings = &mut _model.settings;
+    if model.follow {
+        match model
+            .inspected
+            .and_then(|index| model.world.bodies.get(index))
+        {
+            Some(body) => model.camera.center = body.borrow().position,
+            None => model.follow = false,
+        }
+    }
 
-    egui.set_elapsed_time(_update.since_start);
+    let egui = &mut model.egui;
+    let settings = &mut model.settings;
+
+    egui.set_elapsed_time(update.since_start);
     let ctx = egui.begin_frame();
-    let demo_names = [
-        "Demo 1: Simple Shapes Falling",
-        "Demo 2: Simple Pendulum",
-        "Demo 3: Varying Friction Coefficients",
-        "Demo 4: Randomized Stacking",
-        "Demo 5: Pyramid Stacking",
-        "Demo 6: A Teeter",
-        "Demo 7: A Suspension Bridge",
-        "Demo 8: Dominos",
-        "Demo 9: Multi-pendulum",
-        "Demo 10: A Pawn and the pendulum",
-    ];
+
+    // Pinch to zoom and drag with two fingers to pan
+    if let Some(touch) = ctx.input(|i| i.multi_touch()) {
+        let center = model
+            .camera
+            .from_top_left(touch.start_pos.x, touch.start_pos.y);
+        model.camera.zoom_at(center, touch.zoom_delta);
+        model.camera.pan(Vec2::new(
+            touch.translation_delta.x,
+            -touch.translation_delta.y,
+        ));
+    }
     egui::Window::new("Settings").show(&ctx, |ui| {
         // Dropdown for selecting the demo
         ui.label("Select Demo:");
         egui::ComboBox::from_label("Demo Selection")
-            .selected_text(format!("Demo {}", _model.demo_index + 1))
+            .selected_text(format!("Demo {}", model.demo_index + 1))
             .show_ui(ui, |ui| {
-                for (i, name) in demo_names.iter().enumerate() {
-                    ui.selectable_value(&mut _model.demo_index, i as u32, *name);
+                for (i, scene) in model.scenes.iter().enumerate() {
+                    ui.selectable_value(
+                        &mut model.demo_index,
+                        i as u32,
+                        format!("Demo {}: {}", i + 1, scene.name),
+                    );
                 }
             });
 
         // Button to load the selected demo
         if ui.button("Load Demo").clicked() {
-            _model.load_demo_flag = true;
+            model.actions.push(Action::LoadDemo {
+                index: model.demo_index,
+                seed: model.seed,
+            });
         }
-        // Scale slider
-        ui.label("Scale:");
-        ui.add(egui::Slider::new(&mut settings.scale, 0.0..=100.0));
+        // Camera, also moved with the mouse wheel and by dragging empty space
+        ui.label("Zoom:");
+        ui.add(egui::Slider::new(&mut model.camera.scale, 0.5..=500.0).logarithmic(true));
+        ui.horizontal(|ui| {
+            if ui.button("Fit all bodies").clicked() {
+                model.fit_view_flag = true;
+            }
+            if ui.button("Reset view").clicked() {
+                model.camera = Camera::default();
+            }
+        });
+        ui.add_enabled(
+            model.inspected.is_some(),
+            egui::Checkbox::new(&mut model.follow, "Follow the inspected body"),
+        );
 
-        ui.label("Translate origin X:");
-        ui.add(egui::Slider::new(&mut settings.x_translate, -100.0..=100.0));
+        ui.label("Time step:");
+        let mut time_step = model.time_step;
+        if ui
+            .add(egui::Slider::new(&mut time_step, 1.0 / 240.0..=1.0 / 30.0))
+            .changed()
+        {
+            model.actions.push(Action::SetTimeStep { time_step });
+        }
 
-        ui.label("Translate origin Y:");
-        ui.add(egui::Slider::new(&mut settings.y_translate, -100.0..=100.0));
+        ui.label("Time scale:");
+        ui.add(egui::Slider::new(&mut model.time_scale, 0.0..=4.0));
+
+        ui.label("Gravity:");
+        let mut gravity = to_array(model.world.gravity);
+        let mut gravity_changed = false;
+        ui.horizontal(|ui| {
+            for (value, prefix) in gravity.iter_mut().zip(["x: ", "y: "]) {
+                let drag = egui::DragValue::new(value).speed(0.1).prefix(prefix);
+                gravity_changed |= ui.add(drag).changed();
+            }
+            if ui.button("Zero g").clicked() {
+                gravity = [0.0, 0.0];
+                gravity_changed = true;
+            }
+            if ui.button("Default").clicked() {
+                gravity = [0.0, -10.0];
+                gravity_changed = true;
+            }
+        });
+        if gravity_changed {
+            model.actions.push(Action::SetGravity { gravity });
+        }
+
+        ui.label("Iterations:");
+        let mut iterations = model.world.iterations;
+        if ui
+            .add(egui::Slider::new(&mut iterations, 1..=200))
+            .changed()
+        {
+            model.actions.push(Action::SetIterations { iterations });
+        }
+
+        ui.horizontal(|ui| {
+            let play_label = if model.paused { "Play" } else { "Pause" };
+            if ui.button(play_label).clicked() {
+                model.paused = !model.paused;
+            }
+            if ui.button("Step once").clicked() {
+                model.pending_steps += 1;
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.add(egui::DragValue::new(&mut model.step_count).clamp_range(1..=1000));
+            if ui.button("Step N").clicked() {
+                model.pending_steps += model.step_count;
+            }
+        });
+
+        ui.label(format!("Timeline (step {}):", model.step_index));
+        let mut position = model.history.position();
+        let last = model.history.last_index();
+        let timeline = ui.add(egui::Slider::new(&mut position, 0..=last).show_value(false));
+        if timeline.changed() {
+            model.actions.push(Action::Restore { index: position });
+        }
 
         // Random color button
         let clicked = ui.button("Random color").clicked();
@@ -474,123 +590,407 @@ pub fn update(_app: &App, _model: &mut Model, _update: Update) {
             settings.color = rgb(random(), random(), random());
         }
 
-        if ui.button("launch bomb").clicked() {
-            _model.bomb = true;
+        if ui.button("launch bomb").clicked() && model.editor.is_none() {
+            model.actions.push(Action::Bomb);
+        }
+
+        let edit_label = if model.editor.is_some() {
+            "Leave edit mode"
+        } else {
+            "Edit"
+        };
+        if ui.button(edit_label).clicked() {
+            model.toggle_edit_flag = true;
+        }
+
+        if ui.button("Export scene").clicked() {
+            model.export_scene_flag = true;
         }
 
         // Checkbox to enable a feature
-        ui.checkbox(
-            &mut _model.world.world_context.warm_starting,
-            "Enable/Disable warm starting.",
-        );
-        ui.checkbox(
-            &mut _model.world.world_context.position_correction,
+        let mut flags = WorldFlags::from_world(&model.world);
+        let warm_starting = ui.checkbox(&mut flags.warm_starting, "Enable/Disable warm starting.");
+        let position_correction = ui.checkbox(
+            &mut flags.position_correction,
             "Enable/Disable position correction.",
         );
-        ui.checkbox(
-            &mut _model.world.world_context.accumulate_impulse,
+        let accumulate_impulse = ui.checkbox(
+            &mut flags.accumulate_impulse,
             "Enable/Disable accumulation of impulse.",
         );
+        if warm_starting.changed() || position_correction.changed() || accumulate_impulse.changed()
+        {
+            model.actions.push(Action::SetFlags { flags });
+        }
+        egui::ComboBox::from_label("Body colours")
+            .selected_text(model.color_mode.label())
+            .show_ui(ui, |ui| {
+                for mode in ColorMode::ALL {
+                    ui.selectable_value(&mut model.color_mode, mode, mode.label());
+                }
+            });
+        ui.collapsing("Debug layers", |ui| model.layers.ui(ui));
+        ui.checkbox(&mut model.show_plots, "Show diagnostic plots");
+        ui.collapsing("Diagnostics", |ui| {
+            let diagnostics = &model.diagnostics;
+            egui::Grid::new("diagnostics").show(ui, |ui| {
+                let rows = [
+                    ("Kinetic energy", diagnostics.kinetic_energy),
+                    ("Potential energy", diagnostics.potential_energy),
+                    ("Total energy", diagnostics.total_energy()),
+                    ("Linear momentum x", diagnostics.linear_momentum[0]),
+                    ("Linear momentum y", diagnostics.linear_momentum[1]),
+                    ("Angular momentum", diagnostics.angular_momentum),
+                    ("Max penetration", diagnostics.max_penetration),
+                    ("Max joint drift", diagnostics.max_joint_drift),
+                ];
+                for (label, value) in rows {
+                    ui.label(label);
+                    ui.label(format!("{:.4}", value));
+                    ui.end_row();
+                }
+                ui.label("Contacts");
+                ui.label(diagnostics.contact_count.to_string());
+                ui.end_row();
+            });
+        });
+
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.label("Seed:");
+            ui.add(egui::DragValue::new(&mut model.seed));
+        });
+        ui.horizontal(|ui| {
+            if model.recorder.is_recording() {
+                if ui.button("Stop recording").clicked() {
+                    model.last_recording = model.recorder.stop();
+                }
+            } else if ui.button("Record").clicked() {
+                // A recording starts from a freshly loaded demo
+                let flags = WorldFlags::from_world(&model.world);
+                model.recorder.start(flags, model.time_step);
+                model.actions.push(Action::LoadDemo {
+                    index: model.demo_index,
+                    seed: model.seed,
+                });
+            }
+            if let Some(recording) = &model.last_recording {
+                if ui.button("Replay").clicked() {
+                    model.pending_replay = Some(recording.clone());
+                }
+                if ui.button("Save recording").clicked() {
+                    let result = recording
+                        .to_json()
+                        .map_err(|e| e.to_string())
+                        .and_then(|json| save_text_file("replay.json", &json));
+                    if let Err(e) = result {
+                        eprintln!("Error: {}", e);
+                    }
+                }
+            }
+        });
+        ui.collapsing("Replay a saved recording", |ui| {
+            ui.text_edit_multiline(&mut model.replay_source);
+            if ui.button("Replay").clicked() {
+                match Recording::from_json(&model.replay_source) {
+                    Ok(recording) => model.pending_replay = Some(recording),
+                    Err(e) => eprintln!("Error: {}", e),
+                }
+            }
+        });
+        if let Some(player) = &model.player {
+            let (done, total) = player.progress();
+            ui.label(format!("Replaying event {} of {}", done, total));
+            if ui.button("Stop replay").clicked() {
+                model.player = None;
+            }
+        }
     });
+
+    if let (Some(index), None) = (model.inspected, &model.editor) {
+        let mut open = true;
+        let mut edit = None;
+        egui::Window::new("Body")
+            .open(&mut open)
+            .show(&ctx, |ui| edit = body_inspector_ui(ui, &model.world, index));
+        if let Some(edit) = edit {
+            model.actions.push(Action::EditBody { body: index, edit });
+        }
+        if !open {
+            model.inspected = None;
+        }
+    }
+
+    let plots = &model.plots;
+    egui::Window::new("Diagnostics")
+        .open(&mut model.show_plots)
+        .show(&ctx, |ui| plots.ui(ui));
+
+    if let Some(editor) = &mut model.editor {
+        egui::Window::new("Editor").show(&ctx, |ui| {
+            editor.tools_ui(ui);
+            ui.separator();
+            editor.inspector_ui(ui);
+        });
+    }
+
+    host_ui(&ctx);
 }
 
 fn load_demo(model: &mut Model) {
-    model.world.clear(); // Clear the current world bodies and joints
+    if let Some(scene) = model.scenes.get(model.demo_index as usize).cloned() {
+        load_scene(model, &scene);
+    }
+}
+
+fn load_scene(model: &mut Model, scene: &Scene) {
+    model.mouse_joint = None;
+    model.inspected = None;
+    model.editor = None;
+    model.timestep.reset();
+    model.rng = Pcg32::seed_from_u64(model.seed);
+    // Replaces the current world bodies and joints
+    match scene.load_into(&mut model.world, model.time_step, &mut model.rng) {
+        Ok(()) => model.joint_springs = scene.springs(),
+        Err(e) => eprintln!("Error: {}", e),
+    }
+    let keys = scene.bodies.iter().map(|body| body.key);
+    model.body_keys.reset(&model.world, keys);
+    remote::reset_contacts(&model.remote, &model.world);
+    reset_history(model);
+}
+fn scene_name(model: &Model) -> &str {
+    model
+        .scenes
+        .get(model.demo_index as usize)
+        .map_or("Scene", |scene| scene.name.as_str())
+}
+
+fn export_scene(model: &Model) {
+    let scene = match &model.editor {
+        Some(editor) => Ok(editor.scene.clone()),
+        None => Scene::from_world(scene_name(model), &model.world, &model.joint_springs),
+    };
+    let result = scene.and_then(|scene| {
+        let json = scene.to_json().map_err(|e| e.to_string())?;
+        save_text_file(&scene.file_name(), &json)
+    });
+    if let Err(e) = result {
+        eprintln!("Error: {}", e);
+    }
+}
+
+/// Enters edit mode with the current state of the world, or rebuilds the world
+/// from the edited scene when leaving it.
+fn toggle_edit_mode(model: &mut Model) {
+    model.timestep.reset();
+    if let Some(editor) = model.editor.take() {
+        let seed = model.seed;
+        perform(
+            model,
+            Action::LoadScene {
+                scene: editor.scene,
+                seed,
+            },
+        );
+        return;
+    }
+    if let Some(mouse_joint) = model.mouse_joint.take() {
+        mouse_joint.release(&model.world);
+    }
+    match Scene::from_world(scene_name(model), &model.world, &model.joint_springs) {
+        Ok(mut scene) => {
+            // The bodies keep their colour once the scene is rebuilt
+            for (desc, body) in scene.bodies.iter_mut().zip(model.world.bodies.iter()) {
+                desc.key = model.body_keys.key(body);
+            }
+            model.editor = Some(Editor::new(scene));
+        }
+        Err(e) => eprintln!("Error: {}", e),
+    }
+}
+
+/// The radius of the editor handles in world units.
+fn handle_radius(camera: &Camera) -> f32 {
+    6.0 / camera.scale
+}
+
+/// Zooms and centres the camera on all the bodies of the world, or of the
+/// edited scene.
+fn fit_view(model: &mut Model) {
+    let bodies = match &model.editor {
+        Some(editor) => editor.bodies(),
+        None => model.world.iter_bodies().map(|body| body.clone()).collect(),
+    };
+    let mut bounds: Option<(Vec2, Vec2)> = None;
+    for body in bodies.iter() {
+        let (min, max) = aabb(body, &Pose::of(body));
+        bounds = Some(match bounds {
+            Some((lo, hi)) => (
+                Vec2::new(lo.x.min(min.x), lo.y.min(min.y)),
+                Vec2::new(hi.x.max(max.x), hi.y.max(max.y)),
+            ),
+            None => (min, max),
+        });
+    }
+    if let Some((min, max)) = bounds {
+        model.camera.fit(min, max);
+    }
+}
 
-    match model.demo_index {
-        0 => demo1(model),
-        1 => demo2(model),
-        2 => demo3(model),
-        3 => demo4(model),
-        4 => demo5(model),
-        5 => demo6(model),
-        6 => demo7(model),
-        7 => demo8(model),
-        8 => demo9(model),
-        9 => demo10(model),
-        _ => {}
+pub fn mouse_pressed(app: &App, model: &mut Model, button: MouseButton) {
+    if model.egui.ctx().is_pointer_over_area() {
+        return;
+    }
+    if button == MouseButton::Middle {
+        model.pan_from = Some(app.mouse.position());
+    }
+    if button != MouseButton::Left {
+        return;
+    }
+    let point = model.camera.screen_to_world(app.mouse.position());
+    if let Some(editor) = &mut model.editor {
+        editor.mouse_pressed(point, handle_radius(&model.camera));
+        return;
+    }
+    model.inspected = body_under(&model.world, point);
+    match pick_body(&model.world, point) {
+        Some(body) => {
+            let point = to_array(point);
+            user_action(model, Action::Grab { body, point });
+        }
+        None if model.inspected.is_none() => model.pan_from = Some(app.mouse.position()),
+        None => {}
     }
 }
-fn raw_window_event(_app: &App, model: &mut Model, event: &nannou::winit::event::WindowEvent) {
-    // Let egui handle things like keyboard and mouse input.
-    model.egui.handle_raw_event(event);
+
+pub fn mouse_moved(_app: &App, model: &mut Model, position: Point2) {
+    if let Some(from) = model.pan_from {
+        model
+            .camera
+            .pan(Vec2::new(position.x - from.x, position.y - from.y));
+        model.pan_from = Some(position);
+        return;
+    }
+    let target = model.camera.screen_to_world(position);
+    if let Some(editor) = &mut model.editor {
+        editor.mouse_dragged(target);
+    }
+    if model.mouse_joint.is_some() {
+        let target = to_array(target);
+        user_action(model, Action::Drag { target });
+    }
 }
 
-fn key_pressed(_app: &App, model: &mut Model, key: Key) {
+pub fn mouse_released(_app: &App, model: &mut Model, button: MouseButton) {
+    model.pan_from = None;
+    if button != MouseButton::Left {
+        return;
+    }
+    if let Some(editor) = &mut model.editor {
+        editor.mouse_released();
+    }
+    if model.mouse_joint.is_some() {
+        user_action(model, Action::Release);
+    }
+}
+
+pub fn mouse_wheel(app: &App, model: &mut Model, delta: MouseScrollDelta, _phase: TouchPhase) {
+    if model.egui.ctx().is_pointer_over_area() {
+        return;
+    }
+    let lines = match delta {
+        MouseScrollDelta::LineDelta(_, y) => y,
+        MouseScrollDelta::PixelDelta(position) => position.y as f32 / 50.0,
+    };
+    model
+        .camera
+        .zoom_at(app.mouse.position(), 1.1_f32.powf(lines));
+}
+
+pub fn key_pressed(app: &App, model: &mut Model, key: Key) {
+    if let Some(editor) = &mut model.editor {
+        if matches!(key, Key::Delete | Key::Back) && !model.egui.ctx().wants_keyboard_input() {
+            editor.delete_selected();
+        }
+        return;
+    }
     match key {
         Key::Right => {
-            let _ = model.world.step(model.time_step);
+            model.pending_steps += 1;
+        }
+        Key::Space => {
+            model.paused = !model.paused;
         }
         Key::Left => {
-            let _ = model.world.step(-model.time_step);
+            let position = model.history.position();
+            if position > 0 {
+                user_action(
+                    model,
+                    Action::Restore {
+                        index: position - 1,
+                    },
+                );
+            }
         }
         Key::Return => {
-            println!("Number of bodies {:?}", model.world.bodies.len());
-            println!("World Bodies: {:?}", model.world.bodies);
-            println!("{:?}", model.world.arbiters);
+            // Inspects the body under the mouse, or closes the inspector
+            let point = model.camera.screen_to_world(app.mouse.position());
+            model.inspected = body_under(&model.world, point);
         }
         _other_key => {}
     }
 }
 
-fn view(app: &App, _model: &Model, frame: Frame) {
-    let draw = app.draw();
-    let draw = draw.scale(_model.settings.scale);
-    let draw = draw.x(_model.settings.x_translate);
-    let draw = draw.y(_model.settings.y_translate);
+fn draw_world(draw: &Draw, screen: &Draw, _model: &Model) {
     let settings = &_model.settings;
-    draw.background().color(SLATEGREY);
+    let camera = &_model.camera;
+    let alpha = _model.timestep.alpha(_model.time_step);
+    let mut poses = Vec::with_capacity(_model.world.bodies.len());
+    let colors = body_colors(&_model.world, _model.color_mode, &_model.body_keys);
     for (num, body) in _model.world.iter_bodies().enumerate() {
-        match body.shape {
-            Shape::Box => {
-                draw.rect()
-                    .x_y(body.position.x, body.position.y)
-                    .w_h(body.width.x, body.width.y)
-                    .rotate(body.rotation)
-                    .color(if num == 0 { DARKSEAGREEN } else { ORCHID });
-            }
-            Shape::ConvexPolygon => {
-                let tuples: Vec<(f32, f32)> = body
-                    .get_polygon()
-                    .get_vertices()
-                    .into_iter()
-                    .map(Into::into)
-                    .collect();
-                draw.polygon()
-                    .color(if num == 0 { DARKSEAGREEN } else { ORCHID })
-                    .x_y(body.position.x, body.position.y)
-                    .rotate(body.rotation)
-                    .points(tuples);
-            }
-        }
-    }
-
-    for (_, arbiter) in _model.world.arbiters.iter() {
-        for contact in arbiter.contacts.iter() {
-            match contact {
-                Some(contact) => {
-                    draw.ellipse()
-                        .x_y(contact.position.x, contact.position.y)
-                        .radius(0.1)
-                        .color(settings.color);
-                    draw.arrow()
-                        .start(pt2(contact.position.x, contact.position.y))
-                        .end(pt2(
-                            contact.position.x + contact.normal.x,
-                            contact.position.y + contact.normal.y,
-                        ))
-                        .weight(0.05)
-                        .color(LIGHTSALMON);
-                }
-                None => (),
-            }
-        }
+        let pose = _model.timestep.pose(num, &body, alpha);
+        draw_body(
+            draw,
+            &body,
+            pose.position,
+            pose.rotation,
+            if Some(num) == _model.inspected {
+                LIGHTSKYBLUE.into_lin_srgba()
+            } else {
+                colors[num]
+            },
+        );
+        draw_body_layers(draw, &body, &pose, &_model.layers);
+        poses.push(pose);
     }
+    let pose_of = |body: &Rc<RefCell<Body>>| {
+        _model
+            .world
+            .bodies
+            .iter()
+            .position(|other| Rc::ptr_eq(other, body))
+            .map(|index| poses[index])
+    };
+
+    let to_screen = |p: Vec2| camera.world_to_screen(p);
+    draw_arbiter_layers(
+        draw,
+        screen,
+        to_screen,
+        &_model.world,
+        &poses,
+        &_model.layers,
+        settings.color,
+    );
     for joint in _model.world.joints.iter() {
-        let x1 = joint.body_1.borrow().position;
-        let x2 = joint.body_2.borrow().position;
-        let r1 = Mat2x2::new_from_angle(joint.body_1.borrow().rotation);
-        let r2 = Mat2x2::new_from_angle(joint.body_2.borrow().rotation);
+        let (Some(pose_1), Some(pose_2)) = (pose_of(&joint.body_1), pose_of(&joint.body_2)) else {
+            continue;
+        };
+        let x1 = pose_1.position;
+        let x2 = pose_2.position;
+        let r1 = Mat2x2::new_from_angle(pose_1.rotation);
+        let r2 = Mat2x2::new_from_angle(pose_2.rotation);
         let p1 = x1 + r1 * joint.local_anchor_1;
         let p2 = x2 + r2 * joint.local_anchor_2;
         draw.line()
@@ -604,6 +1004,81 @@ fn view(app: &App, _model: &Model, frame: Frame) {
             .weight(0.05)
             .color(SLATEBLUE);
     }
-    draw.to_frame(app, &frame).unwrap();
-    _model.egui.draw_to_frame(&frame).unwrap();
+    if let Some(mouse_joint) = &_model.mouse_joint {
+        if let Some(anchor) = mouse_joint.anchor(&_model.world) {
+            draw.line()
+                .start(pt2(anchor.x, anchor.y))
+                .end(pt2(mouse_joint.target.x, mouse_joint.target.y))
+                .weight(0.05)
+                .color(GOLD);
+            draw.ellipse()
+                .x_y(anchor.x, anchor.y)
+                .radius(0.1)
+                .color(GOLD);
+        }
+    }
+}
+
+fn draw_editor(draw: &Draw, editor: &Editor, radius: f32) {
+    let bodies = editor.bodies();
+    for (num, body) in bodies.iter().enumerate() {
+        let color = if editor.selected == Some(num) || editor.joint_bodies.contains(&num) {
+            GOLD
+        } else if is_static(body) {
+            DARKSEAGREEN
+        } else {
+            ORCHID
+        };
+        draw_body(draw, body, body.position, body.rotation, color);
+    }
+
+    for (num, joint) in editor.scene.joints.iter().enumerate() {
+        let color = if editor.selected_joint == Some(num) {
+            GOLD
+        } else {
+            SLATEBLUE
+        };
+        for &index in joint.bodies.iter() {
+            if let Some(body) = bodies.get(index) {
+                draw.line()
+                    .start(pt2(body.position.x, body.position.y))
+                    .end(pt2(joint.anchor[0], joint.anchor[1]))
+                    .weight(0.05)
+                    .color(color);
+            }
+        }
+        draw.ellipse()
+            .x_y(joint.anchor[0], joint.anchor[1])
+            .radius(radius)
+            .color(color);
+    }
+
+    let center = editor.handle_position(Handle::Move, radius);
+    let resize = editor.handle_position(Handle::Resize, radius);
+    let rotate = editor.handle_position(Handle::Rotate, radius);
+    if let (Some(center), Some(resize), Some(rotate)) = (center, resize, rotate) {
+        draw.line()
+            .start(pt2(center.x, center.y))
+            .end(pt2(rotate.x, rotate.y))
+            .weight(0.5 * radius)
+            .color(WHITE);
+        for handle in [resize, rotate] {
+            draw.ellipse()
+                .x_y(handle.x, handle.y)
+                .radius(radius)
+                .color(WHITE);
+        }
+    }
+}
+
+pub fn view(app: &App, _model: &Model, frame: &Frame) {
+    let screen = app.draw();
+    let draw = _model.camera.transform(&screen);
+    draw.background().color(SLATEGREY);
+    match &_model.editor {
+        Some(editor) => draw_editor(&draw, editor, handle_radius(&_model.camera)),
+        None => draw_world(&draw, &screen, _model),
+    }
+    draw.to_frame(app, frame).unwrap();
+    _model.egui.draw_to_frame(frame).unwrap();
 }
@@ -0,0 +1,123 @@
+use serde::{Deserialize, Serialize};
+use sylt_2d::joint::Joint;
+use sylt_2d::world::World;
+
+/// A soft constraint described by the oscillator it behaves like.
+///
+/// The joint softness and bias factor depend on the time step, so they are
+/// derived from this description whenever the time step changes.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SpringConfig {
+    pub frequency_hz: f32,
+    pub damping_ratio: f32,
+    pub mass: f32,
+}
+
+impl SpringConfig {
+    pub fn new(frequency_hz: f32, damping_ratio: f32, mass: f32) -> SpringConfig {
+        SpringConfig {
+            frequency_hz,
+            damping_ratio,
+            mass,
+        }
+    }
+
+    fn omega(&self) -> f32 {
+        2.0 * std::f32::consts::PI * self.frequency_hz
+    }
+
+    /// The spring constant `k`.
+    pub fn stiffness(&self) -> f32 {
+        self.mass * self.omega() * self.omega()
+    }
+
+    /// The damping coefficient `d`.
+    pub fn damping(&self) -> f32 {
+        2.0 * self.mass * self.damping_ratio * self.omega()
+    }
+
+    /// Returns the `(softness, bias_factor)` of a joint stepped with `time_step`.
+    pub fn softness_and_bias(&self, time_step: f32) -> (f32, f32) {
+        let d = self.damping();
+        let k = self.stiffness();
+        let softness = 1.0 / (d + time_step * k);
+        let bias_factor = time_step * k / (d + time_step * k);
+        (softness, bias_factor)
+    }
+
+    pub fn apply(&self, joint: &mut Joint, time_step: f32) {
+        (joint.softness, joint.bias_factor) = self.softness_and_bias(time_step);
+    }
+}
+
+/// Recomputes the parameters of the joints of `world` that have a spring
+/// configuration for a new `time_step`.
+pub fn retune_joints(world: &mut World, springs: &[Option<SpringConfig>], time_step: f32) {
+    for (joint, spring) in world.joints.iter_mut().zip(springs) {
+        if let Some(spring) = spring {
+            spring.apply(joint, time_step);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use sylt_2d::body::Body;
+    use sylt_2d::math_utils::Vec2;
+
+    const TIME_STEP: f32 = 1.0 / 60.0;
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!(
+            (actual - expected).abs() <= 1e-6 * expected.abs(),
+            "{} is not {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn matches_the_softness_of_the_bridge_demo() {
+        // The parameters the suspension bridge demo used to compute inline
+        let (mass, frequency_hz, damping_ratio) = (10.0, 2.0, 0.7);
+        let omega = 2.0 * std::f32::consts::PI * frequency_hz;
+        let d = 2.0 * mass * damping_ratio * omega;
+        let k = mass * omega * omega;
+        let softness = 1.0 / (d + TIME_STEP * k);
+        let bias_factor = TIME_STEP * k / (d + TIME_STEP * k);
+
+        let spring = SpringConfig::new(frequency_hz, damping_ratio, mass);
+        let (actual_softness, actual_bias_factor) = spring.softness_and_bias(TIME_STEP);
+        assert_close(actual_softness, softness);
+        assert_close(actual_bias_factor, bias_factor);
+        // The values the scene file had before it described the spring
+        assert_close(actual_softness, 0.004944421);
+        assert_close(actual_bias_factor, 0.130132);
+    }
+
+    #[test]
+    fn retunes_only_the_joints_with_a_spring() {
+        let mut world = World::new(Vec2::new(0.0, -10.0), 10);
+        let body_1 = Body::new(Vec2::new(1.0, 1.0), 1.0);
+        let mut body_2 = Body::new(Vec2::new(1.0, 1.0), 1.0);
+        body_2.position = Vec2::new(2.0, 0.0);
+        world.add_body(body_1.clone());
+        world.add_body(body_2.clone());
+        for _ in 0..2 {
+            let joint = Joint::new(body_1.clone(), body_2.clone(), Vec2::new(1.0, 0.0), &world);
+            world.add_joint(joint);
+        }
+        let rigid = (world.joints[1].softness, world.joints[1].bias_factor);
+
+        let spring = SpringConfig::new(2.0, 0.7, 10.0);
+        retune_joints(&mut world, &[Some(spring), None], TIME_STEP);
+        let joint_parameters = |joint: &Joint| (joint.softness, joint.bias_factor);
+        assert_eq!(
+            joint_parameters(&world.joints[0]),
+            spring.softness_and_bias(TIME_STEP)
+        );
+        assert_eq!(joint_parameters(&world.joints[1]), rigid);
+    }
+}
@@ -0,0 +1,167 @@
+use sylt_2d::body::Body;
+use sylt_2d::math_utils::Vec2;
+use sylt_2d::world::World;
+
+/// The most steps taken in a single frame. When the simulation cannot keep
+/// up, the remaining time is dropped instead of piling up.
+pub const MAX_SUBSTEPS: u32 = 8;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Pose {
+    pub position: Vec2,
+    pub rotation: f32,
+}
+
+impl Pose {
+    pub fn of(body: &Body) -> Pose {
+        Pose {
+            position: body.position,
+            rotation: body.rotation,
+        }
+    }
+
+    fn lerp(&self, other: &Pose, alpha: f32) -> Pose {
+        Pose {
+            position: self.position + (other.position - self.position) * alpha,
+            rotation: self.rotation + (other.rotation - self.rotation) * alpha,
+        }
+    }
+}
+
+/// Runs the simulation at a fixed time step driven by the real elapsed time.
+///
+/// Rendering happens between two steps, so the body poses are interpolated
+/// between the previous and the current step.
+#[derive(Default)]
+pub struct FixedTimestep {
+    accumulator: f32,
+    previous: Vec<Pose>,
+}
+
+impl FixedTimestep {
+    /// Adds `elapsed` seconds and returns how many steps of `time_step` to take.
+    pub fn steps(&mut self, elapsed: f32, time_step: f32) -> u32 {
+        self.accumulator += elapsed;
+        let steps = ((self.accumulator / time_step) as u32).min(MAX_SUBSTEPS);
+        self.accumulator -= steps as f32 * time_step;
+        if steps == MAX_SUBSTEPS {
+            self.accumulator = self.accumulator.min(time_step);
+        }
+        steps
+    }
+
+    /// Remembers the poses of the bodies, to be called before each step.
+    pub fn record(&mut self, world: &World) {
+        self.previous.clear();
+        self.previous
+            .extend(world.iter_bodies().map(|body| Pose::of(&body)));
+    }
+
+    /// Forgets the recorded poses, e.g. when the world is replaced.
+    pub fn reset(&mut self) {
+        self.accumulator = 0.0;
+        self.previous.clear();
+    }
+
+    /// How far the rendered frame is between the previous and the current step.
+    pub fn alpha(&self, time_step: f32) -> f32 {
+        (self.accumulator / time_step).clamp(0.0, 1.0)
+    }
+
+    /// The interpolated pose of the body at `index`.
+    pub fn pose(&self, index: usize, body: &Body, alpha: f32) -> Pose {
+        let current = Pose::of(body);
+        match self.previous.get(index) {
+            Some(previous) => previous.lerp(&current, alpha),
+            None => current,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TIME_STEP: f32 = 0.25;
+
+    /// A world with one body at height `y`.
+    fn world_at(y: f32) -> World {
+        let mut world = World::new(Vec2::new(0.0, -10.0), 10);
+        world.add_body(Body::new(Vec2::new(1.0, 1.0), 1.0));
+        move_to(&world, y);
+        world
+    }
+
+    fn move_to(world: &World, y: f32) {
+        world.bodies[0].borrow_mut().position = Vec2::new(0.0, y);
+    }
+
+    fn rendered_height(timestep: &FixedTimestep, world: &World) -> f32 {
+        let alpha = timestep.alpha(TIME_STEP);
+        timestep.pose(0, &world.bodies[0].borrow(), alpha).position.y
+    }
+
+    #[test]
+    fn takes_the_whole_steps_of_the_elapsed_time() {
+        let mut timestep = FixedTimestep::default();
+        assert_eq!(timestep.steps(0.625, TIME_STEP), 2);
+        assert_eq!(timestep.alpha(TIME_STEP), 0.5);
+        assert_eq!(timestep.steps(0.125, TIME_STEP), 1);
+        assert_eq!(timestep.alpha(TIME_STEP), 0.0);
+        assert_eq!(timestep.steps(0.125, TIME_STEP), 0);
+    }
+
+    #[test]
+    fn caps_the_steps_and_drops_the_time_left() {
+        let mut timestep = FixedTimestep::default();
+        assert_eq!(timestep.steps(10.0, TIME_STEP), MAX_SUBSTEPS);
+        assert_eq!(timestep.alpha(TIME_STEP), 1.0);
+        assert_eq!(timestep.steps(0.0, TIME_STEP), 1);
+        assert_eq!(timestep.steps(0.0, TIME_STEP), 0);
+    }
+
+    #[test]
+    fn alpha_stays_between_zero_and_one() {
+        let mut timestep = FixedTimestep::default();
+        for elapsed in [0.0, 0.1, 0.25, 0.3, 1.0, 5.0, 100.0] {
+            timestep.steps(elapsed, TIME_STEP);
+            let alpha = timestep.alpha(TIME_STEP);
+            assert!((0.0..=1.0).contains(&alpha), "alpha is {}", alpha);
+        }
+    }
+
+    #[test]
+    fn interpolates_between_the_recorded_and_the_current_pose() {
+        let mut timestep = FixedTimestep::default();
+        let world = world_at(0.0);
+        timestep.steps(0.375, TIME_STEP);
+        timestep.record(&world);
+        move_to(&world, 2.0);
+        assert_eq!(rendered_height(&timestep, &world), 1.0);
+    }
+
+    #[test]
+    fn bodies_without_a_recorded_pose_are_not_interpolated() {
+        let mut timestep = FixedTimestep::default();
+        let world = world_at(0.0);
+        timestep.steps(0.375, TIME_STEP);
+        timestep.record(&world);
+        let mut body = Body::new(Vec2::new(1.0, 1.0), 1.0);
+        body.position = Vec2::new(0.0, 3.0);
+        let pose = timestep.pose(1, &body, timestep.alpha(TIME_STEP));
+        assert_eq!(pose.position.y, 3.0);
+    }
+
+    #[test]
+    fn does_not_interpolate_once_paused() {
+        let mut timestep = FixedTimestep::default();
+        let world = world_at(0.0);
+        timestep.steps(0.375, TIME_STEP);
+        timestep.record(&world);
+        move_to(&world, 2.0);
+        // What the app does on each frame while paused
+        timestep.reset();
+        assert_eq!(timestep.alpha(TIME_STEP), 0.0);
+        assert_eq!(rendered_height(&timestep, &world), 2.0);
+    }
+}
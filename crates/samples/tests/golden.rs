@@ -0,0 +1,129 @@
+//! Steps every built-in demo for a fixed number of frames and compares the
+//! final body poses against the files in `tests/golden`.
+//!
+//! After an intended change of behaviour, regenerate the files with
+//!
+//! ```sh
+//! UPDATE_GOLDENS=1 cargo test --no-default-features --test golden
+//! ```
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use samples::headless::{run, BodyState, RunConfig};
+use samples::scene::{builtin_scenes, BUILTIN_SCENES};
+
+const STEPS: u32 = 300;
+const TIME_STEP: f32 = 1.0 / 60.0;
+const SEED: u64 = 1;
+const TOLERANCE: f32 = 1e-3;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Pose {
+    position: [f32; 2],
+    rotation: f32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Golden {
+    steps: u32,
+    time_step: f32,
+    seed: u64,
+    bodies: Vec<Pose>,
+}
+
+fn golden_path(file_name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("golden")
+        .join(file_name)
+}
+
+/// Describes how `actual` differs from `expected`, if it does.
+fn compare(expected: &Golden, actual: &Golden) -> Option<String> {
+    if (expected.steps, expected.time_step, expected.seed)
+        != (actual.steps, actual.time_step, actual.seed)
+    {
+        return Some("run parameters changed, regenerate the golden file".to_string());
+    }
+    if expected.bodies.len() != actual.bodies.len() {
+        return Some(format!(
+            "expected {} bodies, got {}",
+            expected.bodies.len(),
+            actual.bodies.len()
+        ));
+    }
+    for (i, (expected, actual)) in expected.bodies.iter().zip(&actual.bodies).enumerate() {
+        let errors = [
+            expected.position[0] - actual.position[0],
+            expected.position[1] - actual.position[1],
+            expected.rotation - actual.rotation,
+        ];
+        if errors
+            .iter()
+            .any(|error| error.is_nan() || error.abs() > TOLERANCE)
+        {
+            return Some(format!(
+                "body {} is at {:?} rotated by {}, expected {:?} rotated by {}",
+                i, actual.position, actual.rotation, expected.position, expected.rotation
+            ));
+        }
+    }
+    None
+}
+
+#[test]
+fn demos_match_golden_trajectories() {
+    let update = env::var_os("UPDATE_GOLDENS").is_some();
+    let config = RunConfig {
+        steps: STEPS,
+        time_step: TIME_STEP,
+        iterations: None,
+        seed: SEED,
+    };
+
+    let mut failures = Vec::new();
+    for ((file_name, _), scene) in BUILTIN_SCENES.iter().zip(builtin_scenes()) {
+        let world = run(&scene, &config, |_, _| {})
+            .unwrap_or_else(|e| panic!("{} failed to run: {}", file_name, e));
+        let actual = Golden {
+            steps: STEPS,
+            time_step: TIME_STEP,
+            seed: SEED,
+            bodies: BodyState::of_world(&world)
+                .into_iter()
+                .map(|state| Pose {
+                    position: state.position,
+                    rotation: state.rotation,
+                })
+                .collect(),
+        };
+
+        let path = golden_path(file_name);
+        if update {
+            let json = serde_json::to_string_pretty(&actual).unwrap();
+            fs::write(&path, json + "\n").unwrap();
+            continue;
+        }
+        let expected: Golden = match fs::read_to_string(&path) {
+            Ok(source) => serde_json::from_str(&source).unwrap(),
+            Err(e) => {
+                failures.push(format!("{}: {}: {}", file_name, path.display(), e));
+                continue;
+            }
+        };
+        if let Some(difference) = compare(&expected, &actual) {
+            failures.push(format!("{}: {}", file_name, difference));
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "Demos diverged from their golden trajectories, run with UPDATE_GOLDENS=1 \
+         if the change is intended:\n{}",
+        failures.join("\n")
+    );
+}
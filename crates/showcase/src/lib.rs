@@ -0,0 +1,5 @@
+pub mod mount;
+mod placement;
+pub mod sketch;
+
+common::main_web!(sketch::create_model, sketch::update, sketch::event);
@@ -0,0 +1,5 @@
+common::main_native!(
+    showcase::sketch::create_model,
+    showcase::sketch::update,
+    showcase::sketch::event
+);
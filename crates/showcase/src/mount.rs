@@ -0,0 +1,80 @@
+use std::cell::{Cell, RefCell};
+
+use common::link::{page_query, Link};
+use samples::remote::{self, SharedRemote};
+
+/// Where and how an instance of the examples is shown.
+#[derive(Clone)]
+pub struct Mount {
+    /// The id of the canvas to draw in, or of the element to add a canvas to.
+    /// Without it, the canvas is added to the page.
+    pub target: Option<String>,
+    /// The initial size of the canvas in points, else the size of the element
+    /// holding it.
+    pub size: Option<(u32, u32)>,
+    pub link: Link,
+    /// Drives the samples of the instance.
+    pub remote: SharedRemote,
+}
+
+thread_local! {
+    static MOUNTS: RefCell<Vec<Mount>> = RefCell::new(Vec::new());
+    static STARTED: Cell<bool> = Cell::new(false);
+}
+
+/// Adds an instance to show once the app starts. Fails once it has started,
+/// as the windows are built along with the model.
+pub fn add_mount(mount: Mount) -> Result<(), String> {
+    if STARTED.with(Cell::get) {
+        return Err("the instances are mounted before calling main_web".to_string());
+    }
+    MOUNTS.with(|mounts| mounts.borrow_mut().push(mount));
+    Ok(())
+}
+
+/// The instances to show, at least one: without any mounted, a single one in
+/// the page configured by the page URL.
+pub fn take_mounts() -> Vec<Mount> {
+    STARTED.with(|started| started.set(true));
+    let mounts = MOUNTS.with(|mounts| mounts.take());
+    if !mounts.is_empty() {
+        return mounts;
+    }
+    vec![Mount {
+        target: None,
+        size: None,
+        link: Link::parse(&page_query()),
+        remote: remote::shared(),
+    }]
+}
+
+/// Shows an instance of the examples in the element with the id `target`:
+/// in it if it is a canvas, else in a canvas added to it. Called before
+/// `main_web`, once for each instance.
+///
+/// The canvas keeps a size of `width` by `height` points when given, else it
+/// takes the size of the element and follows it as it changes. `query`
+/// configures the instance as a deep link does, as in `mode=collision&demo=3`.
+/// Returns the handle driving the samples of the instance.
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen::prelude::wasm_bindgen]
+pub fn mount(
+    target: &str,
+    width: Option<u32>,
+    height: Option<u32>,
+    query: Option<String>,
+) -> Result<samples::api::Simulation, wasm_bindgen::JsError> {
+    use wasm_bindgen::JsError;
+
+    // Reported now rather than once the app starts
+    crate::placement::Placement::find(target).map_err(|e| JsError::new(&e))?;
+    let remote = SharedRemote::default();
+    add_mount(Mount {
+        target: Some(target.to_string()),
+        size: width.zip(height),
+        link: Link::parse(query.as_deref().unwrap_or_default()),
+        remote: remote.clone(),
+    })
+    .map_err(|e| JsError::new(&e))?;
+    Ok(samples::api::Simulation::with_remote(remote))
+}
@@ -0,0 +1,111 @@
+use nannou::prelude::*;
+use nannou::winit::window::WindowBuilder;
+
+/// Where the canvas of a window goes in the page, and the element whose size
+/// it follows.
+#[cfg(target_arch = "wasm32")]
+pub struct Placement {
+    /// The canvas to draw in, when the target is one.
+    canvas: Option<web_sys::HtmlCanvasElement>,
+    /// The parent of the canvas.
+    container: web_sys::Element,
+    /// The node the canvas goes before, keeping a target canvas in place.
+    next_sibling: Option<web_sys::Node>,
+}
+
+#[cfg(target_arch = "wasm32")]
+impl Placement {
+    /// Finds the element with the id `target` in the page.
+    pub fn find(target: &str) -> Result<Placement, String> {
+        use wasm_bindgen::JsCast;
+
+        let element = web_sys::window()
+            .and_then(|window| window.document())
+            .and_then(|document| document.get_element_by_id(target))
+            .ok_or_else(|| format!("no element with the id {}", target))?;
+        match element.dyn_into::<web_sys::HtmlCanvasElement>() {
+            Ok(canvas) => Ok(Placement {
+                container: canvas
+                    .parent_element()
+                    .ok_or_else(|| format!("the canvas {} is not in the page", target))?,
+                next_sibling: canvas.next_sibling(),
+                canvas: Some(canvas),
+            }),
+            Err(element) => Ok(Placement {
+                canvas: None,
+                container: element,
+                next_sibling: None,
+            }),
+        }
+    }
+
+    /// The window drawing in the target canvas, if any.
+    pub fn window_builder(&self) -> WindowBuilder {
+        use nannou::winit::platform::web::WindowBuilderExtWebSys;
+
+        WindowBuilder::new().with_canvas(self.canvas.clone())
+    }
+
+    /// Moves the canvas of `window` in place, as nannou adds it at the end of
+    /// the page.
+    pub fn place(&self, window: &Window) {
+        use nannou::winit::platform::web::WindowExtWebSys;
+
+        let canvas = window.winit_window().canvas();
+        if self.canvas.is_none() {
+            // An inline canvas leaves a gap for the descenders below it, which
+            // would grow the container with every resize
+            if let Err(e) = canvas.style().set_property("display", "block") {
+                eprintln!("Error: {:?}", e);
+            }
+        }
+        if let Err(e) = self
+            .container
+            .insert_before(&canvas, self.next_sibling.as_ref())
+        {
+            eprintln!("Error: {:?}", e);
+        }
+    }
+
+    /// The size of the content box of the container in points, leaving out
+    /// its padding.
+    pub fn container_size(&self) -> (u32, u32) {
+        let style = web_sys::window()
+            .and_then(|window| window.get_computed_style(&self.container).ok())
+            .flatten();
+        let padding = |side: &str| {
+            style
+                .as_ref()
+                .and_then(|style| style.get_property_value(&format!("padding-{}", side)).ok())
+                .and_then(|value| value.trim_end_matches("px").parse::<f64>().ok())
+                .unwrap_or(0.0)
+        };
+        let width = self.container.client_width() as f64 - padding("left") - padding("right");
+        let height = self.container.client_height() as f64 - padding("top") - padding("bottom");
+        (width.max(0.0) as u32, height.max(0.0) as u32)
+    }
+}
+
+/// Where the canvas of a window goes in the page. There is no page natively,
+/// so there is never a placement.
+#[cfg(not(target_arch = "wasm32"))]
+pub enum Placement {}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Placement {
+    pub fn find(_target: &str) -> Result<Placement, String> {
+        Err("mounting in an element is only supported on the web".to_string())
+    }
+
+    pub fn window_builder(&self) -> WindowBuilder {
+        match *self {}
+    }
+
+    pub fn place(&self, _window: &Window) {
+        match *self {}
+    }
+
+    pub fn container_size(&self) -> (u32, u32) {
+        match *self {}
+    }
+}
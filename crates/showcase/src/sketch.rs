@@ -0,0 +1,309 @@
+use collision_debug::sketch as collision;
+use common::link::{copy_to_clipboard, page_url, Link};
+use common::window::{new_webgl2_window, EguiModel};
+use nannou::event::{Event, WindowEvent};
+use nannou::prelude::*;
+use nannou::winit::event::WindowEvent as RawWindowEvent;
+use nannou_egui::{egui, Egui};
+use samples::sketch as samples;
+
+use crate::mount::{take_mounts, Mount};
+use crate::placement::Placement;
+
+/// The examples hosted in the window. Each keeps its own model, so switching
+/// between them is instant and picks up where it was left.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Samples,
+    Collision,
+}
+
+impl Mode {
+    pub const ALL: [Mode; 2] = [Mode::Samples, Mode::Collision];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Mode::Samples => "Samples",
+            Mode::Collision => "Collision",
+        }
+    }
+
+    /// The name of the mode in links.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Mode::Samples => "samples",
+            Mode::Collision => "collision",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Mode> {
+        Mode::ALL
+            .into_iter()
+            .find(|mode| mode.name().eq_ignore_ascii_case(name))
+    }
+}
+
+/// The examples shown in one window, with a mode and state of their own.
+pub struct Instance {
+    window: window::Id,
+    mode: Mode,
+    samples: samples::Model,
+    collision: collision::Model,
+    /// The last link copied, shown to be copied by hand as well.
+    copied_link: Option<String>,
+    /// Where the canvas is in the page, when mounted in an element without a
+    /// size, to follow the size of the element holding the canvas.
+    followed: Option<Placement>,
+    /// The size of the element holding the canvas, when last resized.
+    container_size: (u32, u32),
+}
+
+pub struct Model {
+    instances: Vec<Instance>,
+}
+
+/// Builds a window for each mounted instance. The events of all the windows go
+/// through `event`, which passes them on to the instance of the window.
+pub async fn create_model(app: &App) -> Model {
+    let mut instances = Vec::new();
+    for mount in take_mounts() {
+        match new_instance(app, mount).await {
+            Ok(instance) => instances.push(instance),
+            Err(e) => eprintln!("Error: {}", e),
+        }
+    }
+    Model { instances }
+}
+
+async fn new_instance(app: &App, mount: Mount) -> Result<Instance, String> {
+    let placement = mount.target.as_deref().map(Placement::find).transpose()?;
+    let container_size = placement
+        .as_ref()
+        .map_or((0, 0), |placement| placement.container_size());
+    let mut builder = new_webgl2_window(app).view(view);
+    if let Some(placement) = &placement {
+        builder = builder.window(placement.window_builder());
+    }
+    match mount.size {
+        Some((width, height)) => builder = builder.size(width, height),
+        None if container_size.0 > 0 && container_size.1 > 0 => {
+            builder = builder.size(container_size.0, container_size.1)
+        }
+        None => {}
+    }
+    let window_id = builder.build_async().await.map_err(|e| e.to_string())?;
+    let window = app.window(window_id).ok_or("the window was closed")?;
+    if let Some(placement) = &placement {
+        placement.place(&window);
+    }
+    let mut samples = samples::new_model(app, window_id);
+    samples::set_remote(&mut samples, mount.remote);
+    let mut instance = Instance {
+        window: window_id,
+        mode: Mode::Samples,
+        samples,
+        collision: collision::new_model(app, window_id),
+        copied_link: None,
+        // A size given to `mount` is kept
+        followed: placement.filter(|_| mount.size.is_none()),
+        container_size,
+    };
+    apply_link(&mut instance, &mount.link);
+    Ok(instance)
+}
+
+/// Shows the mode of the link with its settings applied.
+fn apply_link(instance: &mut Instance, link: &Link) {
+    if let Some(name) = &link.mode {
+        match Mode::from_name(name) {
+            Some(mode) => instance.mode = mode,
+            None => eprintln!("Error: no mode named {}", name),
+        }
+    }
+    match instance.mode {
+        Mode::Samples => samples::apply_link(&mut instance.samples, link),
+        Mode::Collision => collision::apply_link(&mut instance.collision, link),
+    }
+}
+
+/// The link to the current mode and its settings.
+fn link(instance: &Instance) -> Link {
+    let link = match instance.mode {
+        Mode::Samples => samples::link(&instance.samples),
+        Mode::Collision => collision::link(&instance.collision),
+    };
+    Link {
+        mode: Some(instance.mode.name().to_string()),
+        ..link
+    }
+}
+
+pub fn update(app: &App, model: &mut Model, update: Update) {
+    for instance in model.instances.iter_mut() {
+        update_instance(app, instance, update);
+    }
+}
+
+fn update_instance(app: &App, instance: &mut Instance, update: Update) {
+    follow_container(app, instance);
+
+    // The mode selector is part of the interface of the mode, so that one egui
+    // takes the input and draws the window
+    let mode = instance.mode;
+    let mut copy_link = false;
+    let host_ui = |ctx: &egui::Context| {
+        copy_link = mode_ui(ctx, &mut instance.mode, instance.copied_link.as_deref());
+    };
+    match mode {
+        Mode::Samples => samples::update_hosted(app, &mut instance.samples, update, host_ui),
+        Mode::Collision => collision::update_hosted(app, &mut instance.collision, update, host_ui),
+    }
+
+    if copy_link {
+        let url = page_url(&link(instance).to_query());
+        if let Err(e) = copy_to_clipboard(&url) {
+            eprintln!("Error: {}", e);
+        }
+        instance.copied_link = Some(url);
+    }
+}
+
+/// Shows the mode selector, returning whether the link was asked for.
+fn mode_ui(ctx: &egui::Context, mode: &mut Mode, copied_link: Option<&str>) -> bool {
+    let mut copy_link = false;
+    egui::Window::new("Mode")
+        .anchor(egui::Align2::RIGHT_TOP, [-10.0, 10.0])
+        .title_bar(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                for option in Mode::ALL {
+                    ui.selectable_value(mode, option, option.label());
+                }
+                copy_link = ui.button("Copy link").clicked();
+            });
+            if let Some(mut text) = copied_link {
+                ui.add(egui::TextEdit::singleline(&mut text).desired_width(300.0));
+            }
+        });
+    copy_link
+}
+
+/// Resizes the window when the element holding its canvas changes size, for
+/// instances mounted without a size.
+fn follow_container(app: &App, instance: &mut Instance) {
+    let Some(placement) = &instance.followed else {
+        return;
+    };
+    let size = placement.container_size();
+    if size == instance.container_size || size.0 == 0 || size.1 == 0 {
+        return;
+    }
+    instance.container_size = size;
+    if let Some(window) = app.window(instance.window) {
+        window.set_inner_size_points(size.0 as f32, size.1 as f32);
+    }
+}
+
+pub fn event(app: &App, model: &mut Model, event: Event) {
+    let Event::WindowEvent { id, raw, simple } = event else {
+        return;
+    };
+    let Some(instance) = model
+        .instances
+        .iter_mut()
+        .find(|instance| instance.window == id)
+    else {
+        return;
+    };
+    raw_window_event(instance, &raw);
+    match simple {
+        Some(WindowEvent::KeyPressed(key)) => key_pressed(app, instance, key),
+        Some(WindowEvent::MousePressed(button)) => mouse_pressed(app, instance, button),
+        Some(WindowEvent::MouseMoved(position)) => mouse_moved(app, instance, position),
+        Some(WindowEvent::MouseReleased(button)) => mouse_released(app, instance, button),
+        Some(WindowEvent::MouseWheel(delta, phase)) => mouse_wheel(app, instance, delta, phase),
+        _ => {}
+    }
+}
+
+/// The egui of the mode shown, which also has the mode selector.
+fn mode_egui(instance: &mut Instance) -> &mut Egui {
+    match instance.mode {
+        Mode::Samples => instance.samples.egui(),
+        Mode::Collision => instance.collision.egui(),
+    }
+}
+
+fn raw_window_event(instance: &mut Instance, event: &RawWindowEvent) {
+    // The hidden mode only follows the size of the window, so that its input
+    // does not pile up until it is shown again
+    let resized = matches!(
+        event,
+        RawWindowEvent::Resized(_) | RawWindowEvent::ScaleFactorChanged { .. }
+    );
+    if resized || instance.mode == Mode::Samples {
+        instance.samples.egui().handle_raw_event(event);
+    }
+    if resized || instance.mode == Mode::Collision {
+        instance.collision.egui().handle_raw_event(event);
+    }
+}
+
+fn key_pressed(app: &App, instance: &mut Instance, key: Key) {
+    if mode_egui(instance).ctx().wants_keyboard_input() {
+        return;
+    }
+    match instance.mode {
+        Mode::Samples => samples::key_pressed(app, &mut instance.samples, key),
+        Mode::Collision => collision::key_pressed(app, &mut instance.collision, key),
+    }
+}
+
+fn mouse_pressed(app: &App, instance: &mut Instance, button: MouseButton) {
+    if mode_egui(instance).ctx().is_pointer_over_area() {
+        return;
+    }
+    match instance.mode {
+        Mode::Samples => samples::mouse_pressed(app, &mut instance.samples, button),
+        Mode::Collision => collision::mouse_pressed(app, &mut instance.collision, button),
+    }
+}
+
+fn mouse_moved(app: &App, instance: &mut Instance, position: Point2) {
+    match instance.mode {
+        Mode::Samples => samples::mouse_moved(app, &mut instance.samples, position),
+        Mode::Collision => collision::mouse_moved(app, &mut instance.collision, position),
+    }
+}
+
+fn mouse_released(app: &App, instance: &mut Instance, button: MouseButton) {
+    match instance.mode {
+        Mode::Samples => samples::mouse_released(app, &mut instance.samples, button),
+        Mode::Collision => collision::mouse_released(app, &mut instance.collision, button),
+    }
+}
+
+fn mouse_wheel(app: &App, instance: &mut Instance, delta: MouseScrollDelta, phase: TouchPhase) {
+    if mode_egui(instance).ctx().is_pointer_over_area() {
+        return;
+    }
+    match instance.mode {
+        Mode::Samples => samples::mouse_wheel(app, &mut instance.samples, delta, phase),
+        Mode::Collision => collision::mouse_wheel(app, &mut instance.collision, delta, phase),
+    }
+}
+
+fn view(app: &App, model: &Model, frame: Frame) {
+    let Some(instance) = model
+        .instances
+        .iter()
+        .find(|instance| instance.window == frame.window_id())
+    else {
+        return;
+    };
+    match instance.mode {
+        Mode::Samples => samples::view(app, &instance.samples, &frame),
+        Mode::Collision => collision::view(app, &instance.collision, &frame),
+    }
+}